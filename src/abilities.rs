@@ -1,9 +1,14 @@
 use std::borrow::Cow;
 use std::collections::HashSet;
-use std::ffi::CStr;
+use std::ffi::{CStr,CString};
+use std::mem;
+use std::ptr;
 
+use ::context::Context;
 use ::port::{PortType};
 
+use ::handle::prelude::*;
+
 /// Describes the abilities of a device.
 ///
 /// ## Example
@@ -220,8 +225,165 @@ impl Abilities {
     }
 }
 
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Abilities {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok,S::Error>
+        where S: ::serde::Serializer
+    {
+        use ::serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Abilities", 13)?;
+        state.serialize_field("device_type", &self.device_type())?;
+        state.serialize_field("model", &self.model())?;
+        state.serialize_field("driver_status", &self.driver_status())?;
+        state.serialize_field("port_types", &self.port_types())?;
+        state.serialize_field("speeds", &self.speeds())?;
+        state.serialize_field("camera_operations", &self.camera_operations())?;
+        state.serialize_field("file_operations", &self.file_operations())?;
+        state.serialize_field("folder_operations", &self.folder_operations())?;
+        state.serialize_field("usb_vendor", &self.usb_vendor())?;
+        state.serialize_field("usb_product", &self.usb_product())?;
+        state.serialize_field("usb_class", &self.usb_class())?;
+        state.serialize_field("usb_subclass", &self.usb_subclass())?;
+        state.serialize_field("usb_protocol", &self.usb_protocol())?;
+        state.end()
+    }
+}
+
+/// The database of camera models supported by `libgphoto2`.
+///
+/// ## Example
+///
+/// An `AbilitiesList` can be used to list all supported cameras without opening a camera:
+///
+/// ```no_run
+/// let mut context = gphoto::Context::new().unwrap();
+/// let list = gphoto::AbilitiesList::load(&mut context).unwrap();
+///
+/// for abilities in list.iter() {
+///     println!("{} ({:?})", abilities.model(), abilities.driver_status());
+/// }
+///
+/// if let Some(abilities) = list.find_usb(0x04b0, 0x0437) {
+///     println!("found {}", abilities.model());
+/// }
+/// ```
+pub struct AbilitiesList {
+    list: *mut ::gphoto2::CameraAbilitiesList,
+}
+
+impl Drop for AbilitiesList {
+    fn drop(&mut self) {
+        unsafe {
+            ::gphoto2::gp_abilities_list_free(self.list);
+        }
+    }
+}
+
+impl AbilitiesList {
+    /// Loads the abilities of all camera models supported by the installed camera drivers.
+    pub fn load(context: &mut Context) -> ::Result<Self> {
+        let mut ptr = ptr::null_mut();
+
+        try_unsafe!(::gphoto2::gp_abilities_list_new(&mut ptr));
+
+        let list = AbilitiesList { list: ptr };
+
+        try_context!(context, ::gphoto2::gp_abilities_list_load(list.list, context.as_mut_ptr()));
+
+        Ok(list)
+    }
+
+    /// Returns the number of camera models in the list.
+    pub fn len(&self) -> usize {
+        let count = unsafe { ::gphoto2::gp_abilities_list_count(self.list) };
+
+        if count < 0 { 0 } else { count as usize }
+    }
+
+    /// Returns `true` if the list contains no camera models.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the abilities of the camera model at `index`.
+    ///
+    /// Returns `None` if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> Option<Abilities> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let mut abilities = unsafe { mem::zeroed() };
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_abilities_list_get_abilities(self.list, index as i32, &mut abilities));
+        }
+
+        Some(from_libgphoto2(abilities))
+    }
+
+    /// Returns an iterator over the abilities of all camera models in the list.
+    pub fn iter(&self) -> AbilitiesIter {
+        AbilitiesIter {
+            list: self,
+            index: 0,
+        }
+    }
+
+    /// Finds a camera model by its name, e.g., `"Nikon DSC D750"`.
+    pub fn find_model(&self, model: &str) -> Option<Abilities> {
+        let cstr = match CString::new(model) {
+            Ok(s) => s,
+            Err(_) => return None
+        };
+
+        match unsafe { ::gphoto2::gp_abilities_list_lookup_model(self.list, cstr.as_ptr()) } {
+            index if index >= 0 => self.get(index as usize),
+            _ => None
+        }
+    }
+
+    /// Finds a camera model by its USB vendor and product IDs.
+    pub fn find_usb(&self, vendor: u16, product: u16) -> Option<Abilities> {
+        self.iter().find(|abilities| {
+            abilities.usb_vendor() == vendor && abilities.usb_product() == product
+        })
+    }
+}
+
+impl<'a> IntoIterator for &'a AbilitiesList {
+    type Item = Abilities;
+    type IntoIter = AbilitiesIter<'a>;
+
+    fn into_iter(self) -> AbilitiesIter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the abilities in an `AbilitiesList`.
+pub struct AbilitiesIter<'a> {
+    list: &'a AbilitiesList,
+    index: usize,
+}
+
+impl<'a> Iterator for AbilitiesIter<'a> {
+    type Item = Abilities;
+
+    fn next(&mut self) -> Option<Abilities> {
+        let abilities = self.list.get(self.index);
+
+        if abilities.is_some() {
+            self.index += 1;
+        }
+
+        abilities
+    }
+}
+
 /// Types of devices.
 #[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
 pub enum DeviceType {
     /// Still camera.
     Camera,
@@ -232,6 +394,7 @@ pub enum DeviceType {
 
 /// Stability of camera driver.
 #[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
 pub enum DriverStatus {
     /// Driver is production ready.
     Production,
@@ -248,6 +411,7 @@ pub enum DriverStatus {
 
 /// Operations that can be performed on a device.
 #[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
 pub enum CameraOperation {
     /// Camera can be configured.
     Config,
@@ -271,6 +435,7 @@ pub enum CameraOperation {
 
 /// Operations that can be performed on files on a device's storage.
 #[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
 pub enum FileOperation {
     /// Files can be deleted.
     Delete,
@@ -290,6 +455,7 @@ pub enum FileOperation {
 
 /// Operations that can be performed on folders on a device's storage.
 #[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
 pub enum FolderOperation {
     /// Deleting all files on the device is supported.
     DeleteAll,
@@ -309,3 +475,8 @@ pub enum FolderOperation {
 pub fn from_libgphoto2(abilities: ::gphoto2::CameraAbilities) -> Abilities {
     Abilities { inner: abilities }
 }
+
+#[doc(hidden)]
+pub fn as_libgphoto2(abilities: &Abilities) -> &::gphoto2::CameraAbilities {
+    &abilities.inner
+}
@@ -0,0 +1,209 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{self,Poll};
+use std::thread;
+use std::time::Duration;
+
+use ::futures_core::Stream;
+use ::tokio::sync::{mpsc,oneshot};
+
+use ::camera::{Camera,CameraEvent,CameraFile};
+use ::context::Context;
+use ::media::{FileMedia,MemoryMedia};
+
+type Job = Box<dyn FnOnce(&mut Camera, &mut Context) + Send>;
+
+/// A camera that's operated asynchronously.
+///
+/// An `AsyncCamera` takes ownership of a `Camera` and a `Context` and moves them to a dedicated
+/// thread, which performs the camera's operations one at a time in the order they were requested.
+/// Each operation returns a future that resolves when the operation is complete, so an async task
+/// isn't blocked while the camera is busy. Dropping a future doesn't cancel its operation.
+///
+/// An `AsyncCamera` is cheap to clone. The camera's thread exits once every clone has been
+/// dropped and the pending operations have finished. This type is only available with the `tokio`
+/// feature.
+///
+/// ## Example
+///
+/// ```no_run,edition2018
+/// async fn capture(camera: &gphoto::AsyncCamera) -> gphoto::Result<()> {
+///     let capture = camera.capture_image().await?;
+///     let path = capture.basename().into_owned();
+///
+///     camera.download(&capture, path).await
+/// }
+///
+/// let mut context = gphoto::Context::new().unwrap();
+/// let camera = gphoto::Camera::autodetect(&mut context).unwrap();
+/// let camera = gphoto::AsyncCamera::new(camera, context);
+/// ```
+#[derive(Clone)]
+pub struct AsyncCamera {
+    jobs: mpsc::UnboundedSender<Job>,
+}
+
+impl AsyncCamera {
+    /// Moves `camera` and `context` to a new thread that operates the camera.
+    ///
+    /// The context is used for every operation, so callbacks registered on it receive feedback
+    /// about all of the camera's operations.
+    pub fn new(camera: Camera, context: Context) -> Self {
+        let (jobs, mut queue) = mpsc::unbounded_channel::<Job>();
+
+        thread::spawn(move || {
+            let mut camera = camera;
+            let mut context = context;
+
+            while let Some(job) = queue.blocking_recv() {
+                job(&mut camera, &mut context);
+            }
+        });
+
+        AsyncCamera { jobs: jobs }
+    }
+
+    /// Performs an arbitrary operation on the camera's thread.
+    ///
+    /// This can be used for operations that don't have an asynchronous counterpart, e.g., changing
+    /// a configuration setting.
+    ///
+    /// ## Errors
+    ///
+    /// Besides the errors returned by `f`, the future resolves to an error of kind `Other` if the
+    /// camera's thread has stopped because a previous operation panicked.
+    pub fn with<F,T>(&self, f: F) -> Reply<T>
+        where F: FnOnce(&mut Camera, &mut Context) -> ::Result<T> + Send + 'static,
+              T: Send + 'static
+    {
+        let (reply, receiver) = oneshot::channel();
+
+        // If the thread has stopped, the job is dropped along with `reply`, which resolves the
+        // future with an error.
+        let _ = self.jobs.send(Box::new(move |camera: &mut Camera, context: &mut Context| {
+            let _ = reply.send(f(camera, context));
+        }));
+
+        Reply { receiver: receiver }
+    }
+
+    /// Captures an image.
+    ///
+    /// See `Camera::capture_image()`.
+    pub fn capture_image(&self) -> Reply<CameraFile> {
+        self.with(|camera, context| camera.capture_image(context))
+    }
+
+    /// Downloads a file from the camera to a new file at `path`.
+    ///
+    /// See `Camera::download()`. Fails with an error if `path` already exists.
+    pub fn download<P: Into<PathBuf>>(&self, source: &CameraFile, path: P) -> Reply<()> {
+        let source = source.clone();
+        let path = path.into();
+
+        self.with(move |camera, context| {
+            let mut file = FileMedia::create(&path)?;
+            camera.download(context, &source, &mut file)
+        })
+    }
+
+    /// Downloads a file from the camera into memory.
+    ///
+    /// See `Camera::download()`.
+    pub fn download_to_vec(&self, source: &CameraFile) -> Reply<Vec<u8>> {
+        let source = source.clone();
+
+        self.with(move |camera, context| {
+            let mut media = MemoryMedia::new()?;
+            camera.download(context, &source, &mut media)?;
+            Ok(media.to_vec())
+        })
+    }
+
+    /// Waits for the camera to report an event.
+    ///
+    /// See `Camera::wait_for_event()`. Other operations are queued until the wait is over.
+    pub fn wait_for_event(&self, timeout: Duration) -> Reply<CameraEvent> {
+        self.with(move |camera, context| camera.wait_for_event(context, timeout))
+    }
+
+    /// Returns a stream of the events reported by the camera.
+    ///
+    /// The stream waits for events for up to `timeout` at a time, which bounds how long other
+    /// operations are queued behind it. `Timeout` events are skipped. Errors are yielded as items,
+    /// after which the stream continues waiting for events. The stream ends when the camera's
+    /// thread stops.
+    pub fn events(&self, timeout: Duration) -> EventStream {
+        EventStream {
+            camera: self.clone(),
+            timeout: timeout,
+            pending: None,
+        }
+    }
+}
+
+/// A future that resolves to the result of an operation performed by an `AsyncCamera`.
+pub struct Reply<T> {
+    receiver: oneshot::Receiver<::Result<T>>,
+}
+
+impl<T> Future for Reply<T> {
+    type Output = ::Result<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<::Result<T>> {
+        match Pin::new(&mut self.receiver).poll(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(util::stopped())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A stream of the events reported by an `AsyncCamera`.
+///
+/// An `EventStream` is created by `AsyncCamera::events()`.
+pub struct EventStream {
+    camera: AsyncCamera,
+    timeout: Duration,
+    pending: Option<Reply<CameraEvent>>,
+}
+
+impl Stream for EventStream {
+    type Item = ::Result<CameraEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Option<::Result<CameraEvent>>> {
+        let this = &mut *self;
+
+        loop {
+            if this.pending.is_none() {
+                if this.camera.jobs.is_closed() {
+                    return Poll::Ready(None);
+                }
+
+                this.pending = Some(this.camera.wait_for_event(this.timeout));
+            }
+
+            let result = match this.pending.as_mut().map(|reply| Pin::new(reply).poll(cx)) {
+                Some(Poll::Ready(result)) => result,
+                _ => return Poll::Pending,
+            };
+
+            this.pending = None;
+
+            match result {
+                Ok(CameraEvent::Timeout) => continue,
+                Ok(event) => return Poll::Ready(Some(Ok(event))),
+                Err(_) if this.camera.jobs.is_closed() => return Poll::Ready(None),
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            }
+        }
+    }
+}
+
+
+mod util {
+    pub fn stopped() -> ::Error {
+        ::error::from_libgphoto2_with_detail(::gphoto2::GP_ERROR, Some("The camera's thread has stopped".to_owned()))
+    }
+}
@@ -1,20 +1,42 @@
 use std::borrow::Cow;
+use std::cmp;
 use std::ffi::CStr;
+use std::fmt;
 use std::mem;
+use std::ptr;
+use std::slice;
+use std::time::{Duration,Instant};
 
+use ::libc::{c_char,c_int,c_void};
+
+use ::claim::ClaimPolicy;
 use ::context::Context;
-use ::abilities::Abilities;
-use ::media::Media;
+use ::file_info::FileInfo;
+use ::list::CameraList;
+use ::abilities::{Abilities,CameraOperation};
+use ::media::{Media,MemoryMedia};
 use ::port::Port;
-use ::storage::Storage;
+use ::storage::{Storage,AccessType};
+use ::walk::Walk;
+use ::widget::{Widget,WidgetValue};
 
 use ::handle::prelude::*;
 
 /// A structure representing a camera connected to the system.
+///
+/// ## Threads
+///
+/// `libgphoto2` doesn't allow a camera to be operated by more than one thread at a time, but it
+/// doesn't care which thread operates it. A `Camera` is therefore `Send` but not `Sync`: it can be
+/// moved to a worker thread, and every operation requires `&mut self`. To operate one camera from
+/// several threads, wrap it in a `SharedCamera`, which serializes the operations.
 pub struct Camera {
     camera: *mut ::gphoto2::Camera,
 }
 
+// See "Threads" in the documentation of `Camera`.
+unsafe impl Send for Camera {}
+
 impl Drop for Camera {
     fn drop(&mut self) {
         unsafe {
@@ -26,45 +48,584 @@ impl Drop for Camera {
 impl Camera {
     /// Opens the first detected camera.
     pub fn autodetect(context: &mut Context) -> ::Result<Self> {
-        let mut ptr = unsafe { mem::uninitialized() };
+        Camera::autodetect_with_policy(context, ClaimPolicy::Fail)
+    }
+
+    /// Opens the first detected camera, handling a camera claimed by another process according to
+    /// `policy`.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// let mut context = gphoto::Context::new().unwrap();
+    /// let policy = gphoto::ClaimPolicy::Retry { attempts: 4, delay: Duration::from_millis(250) };
+    ///
+    /// match gphoto::Camera::autodetect_with_policy(&mut context, policy) {
+    ///     Ok(_) => println!("opened camera"),
+    ///     Err(err) => {
+    ///         for claimant in err.claimants() {
+    ///             println!("camera is in use by {} ({})", claimant.name(), claimant.pid());
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the camera could not be opened:
+    ///
+    /// * `UsbClaim` if another process still has the camera open after all retries. The error's
+    ///   `claimants()` names the processes holding the camera, if they can be found.
+    pub fn autodetect_with_policy(context: &mut Context, policy: ClaimPolicy) -> ::Result<Self> {
+        util::init_with_policy(context, policy, |_| Camera::new())
+    }
+
+    /// Opens a specific camera.
+    ///
+    /// The camera is identified by its `model` name and the `port_path` it's connected to, e.g.,
+    /// `"usb:020,007"`. Use `Context::detect_cameras()` to find the models and ports of all
+    /// connected cameras.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// let mut context = gphoto::Context::new().unwrap();
+    ///
+    /// let cameras = context.detect_cameras().unwrap().into_iter().map(|(model, port)| {
+    ///     gphoto::Camera::open(&mut context, &model, &port).unwrap()
+    /// }).collect::<Vec<_>>();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the camera could not be opened:
+    ///
+    /// * `ModelNotFound` if no driver supports the camera model.
+    /// * `PortNotFound` if the port path is not known.
+    /// * `UsbClaim` if another process has already opened the camera. The error's `claimants()`
+    ///   names the processes holding the camera, if they can be found.
+    pub fn open(context: &mut Context, model: &str, port_path: &str) -> ::Result<Self> {
+        Camera::open_with_policy(context, model, port_path, ClaimPolicy::Fail)
+    }
+
+    /// Opens a specific camera, handling a camera claimed by another process according to
+    /// `policy`.
+    ///
+    /// See `open()` and `autodetect_with_policy()` for details.
+    pub fn open_with_policy(context: &mut Context, model: &str, port_path: &str, policy: ClaimPolicy) -> ::Result<Self> {
+        util::init_with_policy(context, policy, |context| {
+            let camera = Camera::new()?;
+
+            util::set_abilities(camera.camera, context, model)?;
+            util::set_port_info(camera.camera, port_path)?;
+
+            Ok(camera)
+        })
+    }
+
+    fn new() -> ::Result<Self> {
+        let mut ptr = ptr::null_mut();
 
         try_unsafe!(::gphoto2::gp_camera_new(&mut ptr));
 
-        let camera = Camera { camera: ptr };
+        Ok(Camera { camera: ptr })
+    }
 
-        try_unsafe!(::gphoto2::gp_camera_init(camera.camera, context.as_mut_ptr()));
+    fn init(&mut self, context: &mut Context) -> ::Result<()> {
+        try_context!(context, ::gphoto2::gp_camera_init(self.camera, context.as_mut_ptr()));
 
-        Ok(camera)
+        Ok(())
     }
 
     /// Captures an image.
     pub fn capture_image(&mut self, context: &mut Context) -> ::Result<CameraFile> {
+        self.capture(context, CaptureType::Image)
+    }
+
+    /// Captures an image, a movie, or a sound clip.
+    ///
+    /// Returns the file that was captured, which is stored on the camera and can be retrieved with
+    /// `download()`. Most cameras can't capture movies this way; they record movies with
+    /// `start_movie()` and `stop_movie()` instead.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the capture failed:
+    ///
+    /// * `NotSupported` if the camera can't capture the requested type.
+    pub fn capture(&mut self, context: &mut Context, capture_type: CaptureType) -> ::Result<CameraFile> {
         let mut file_path = unsafe { mem::uninitialized() };
 
-        try_unsafe! {
+        try_context!(context, {
             ::gphoto2::gp_camera_capture(self.camera,
-                                         ::gphoto2::GP_CAPTURE_IMAGE,
+                                         capture_type.as_libgphoto2(),
                                          &mut file_path,
                                          context.as_mut_ptr())
-        };
+        });
 
         Ok(CameraFile { inner: file_path })
     }
 
+    /// Starts recording a movie.
+    ///
+    /// Recording is started by turning on the camera's `movie` setting. Cameras that only record
+    /// movies in live view, such as Canon EOS cameras, have their `viewfinder` setting turned on
+    /// first. Call `stop_movie()` to stop recording and retrieve the movie.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// let mut context = gphoto::Context::new().unwrap();
+    /// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+    ///
+    /// camera.start_movie(&mut context).unwrap();
+    /// thread::sleep(Duration::from_secs(10));
+    /// let movie = camera.stop_movie(&mut context, Duration::from_secs(30)).unwrap();
+    ///
+    /// let mut file = gphoto::FileMedia::create(Path::new(&*movie.basename())).unwrap();
+    /// camera.download(&mut context, &movie, &mut file).unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if recording could not be started:
+    ///
+    /// * `NotSupported` if the camera has no `movie` setting.
+    pub fn start_movie(&mut self, context: &mut Context) -> ::Result<()> {
+        util::ignore_missing_setting(self.set_config_value(context, "viewfinder", "1"))?;
+
+        match self.set_config_value(context, "movie", "1") {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                // Recording didn't start, so the camera shouldn't be left in live view.
+                let _ = util::ignore_missing_setting(self.set_config_value(context, "viewfinder", "0"));
+
+                if err.kind() == ::ErrorKind::SettingNotFound {
+                    Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_NOT_SUPPORTED))
+                }
+                else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    /// Stops recording a movie that was started with `start_movie()`.
+    ///
+    /// Returns the recorded movie, which is stored on the camera and can be retrieved with
+    /// `download()`. The movie is reported by the camera once it has finished writing the file,
+    /// which is waited for up to `timeout`. Events other than the new file are discarded while
+    /// waiting. The camera's `viewfinder` setting is turned off again even if this fails.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if recording could not be stopped:
+    ///
+    /// * `NotSupported` if the camera has no `movie` setting.
+    /// * `Timeout` if the camera didn't report the movie within `timeout`.
+    pub fn stop_movie(&mut self, context: &mut Context, timeout: Duration) -> ::Result<CameraFile> {
+        let movie = self.finish_movie(context, timeout);
+        let viewfinder = util::ignore_missing_setting(self.set_config_value(context, "viewfinder", "0"));
+
+        let movie = movie?;
+        viewfinder?;
+
+        Ok(movie)
+    }
+
+    fn finish_movie(&mut self, context: &mut Context, timeout: Duration) -> ::Result<CameraFile> {
+        match self.set_config_value(context, "movie", "0") {
+            Err(ref err) if err.kind() == ::ErrorKind::SettingNotFound => {
+                return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_NOT_SUPPORTED));
+            },
+            result => result?
+        }
+
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let now = Instant::now();
+
+            if now >= deadline {
+                return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_TIMEOUT));
+            }
+
+            if let CameraEvent::FileAdded(file) = self.wait_for_event(context, deadline - now)? {
+                return Ok(file);
+            }
+        }
+    }
+
+    /// Triggers the camera to capture an image without downloading it.
+    ///
+    /// Unlike `capture_image()`, this function returns as soon as the capture has been triggered.
+    /// The captured file is reported by a `FileAdded` event, which can be received with
+    /// `wait_for_event()`.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the capture could not be triggered:
+    ///
+    /// * `NotSupported` if the camera can't trigger captures.
+    pub fn trigger_capture(&mut self, context: &mut Context) -> ::Result<()> {
+        try_context!(context, ::gphoto2::gp_camera_trigger_capture(self.camera, context.as_mut_ptr()));
+
+        Ok(())
+    }
+
+    /// Waits for an event from the camera.
+    ///
+    /// Returns the first event that the camera reports within `timeout`, or `CameraEvent::Timeout`
+    /// if no event is reported before the timeout expires.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// let mut context = gphoto::Context::new().unwrap();
+    /// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+    ///
+    /// camera.trigger_capture(&mut context).unwrap();
+    ///
+    /// loop {
+    ///     match camera.wait_for_event(&mut context, Duration::from_secs(5)).unwrap() {
+    ///         gphoto::CameraEvent::FileAdded(file) => println!("captured {:?}", file.basename()),
+    ///         gphoto::CameraEvent::CaptureComplete => break,
+    ///         gphoto::CameraEvent::Timeout => break,
+    ///         _ => (),
+    ///     }
+    /// }
+    /// ```
+    pub fn wait_for_event(&mut self, context: &mut Context, timeout: Duration) -> ::Result<CameraEvent> {
+        let millis = timeout.as_secs().saturating_mul(1000).saturating_add(timeout.subsec_nanos() as u64 / 1_000_000);
+        let millis = cmp::min(millis, c_int::MAX as u64) as c_int;
+
+        let mut event_type: c_int = 0;
+        let mut event_data: *mut c_void = ptr::null_mut();
+
+        try_context!(context, {
+            ffi::gp_camera_wait_for_event(self.camera,
+                                          millis,
+                                          &mut event_type,
+                                          &mut event_data,
+                                          context.as_mut_ptr())
+        });
+
+        let event = match (event_type, event_data.is_null()) {
+            (ffi::GP_EVENT_TIMEOUT, _) => CameraEvent::Timeout,
+            (ffi::GP_EVENT_CAPTURE_COMPLETE, _) => CameraEvent::CaptureComplete,
+            (ffi::GP_EVENT_FILE_ADDED, false) => {
+                CameraEvent::FileAdded(unsafe { util::read_file_path(event_data) })
+            },
+            (ffi::GP_EVENT_FILE_CHANGED, false) => {
+                CameraEvent::FileChanged(unsafe { util::read_file_path(event_data) })
+            },
+            (ffi::GP_EVENT_FOLDER_ADDED, false) => {
+                let folder = unsafe { util::read_file_path(event_data) };
+                CameraEvent::FolderAdded(util::join_path(&folder.directory(), &folder.basename()))
+            },
+            (ffi::GP_EVENT_UNKNOWN, false) => {
+                CameraEvent::Unknown(unsafe {
+                    String::from_utf8_lossy(CStr::from_ptr(event_data as *const c_char).to_bytes()).into_owned()
+                })
+            },
+            _ => CameraEvent::Unknown(String::new()),
+        };
+
+        if !event_data.is_null() {
+            unsafe {
+                ::libc::free(event_data);
+            }
+        }
+
+        Ok(event)
+    }
+
+    /// Captures a preview image from the camera's viewfinder.
+    ///
+    /// The preview is returned as an in-memory buffer without being stored on the camera. For
+    /// most cameras, the preview is a JPEG image, which makes this suitable for implementing a
+    /// live view.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the preview could not be captured:
+    ///
+    /// * `NotSupported` if the camera can't capture previews.
+    pub fn capture_preview(&mut self, context: &mut Context) -> ::Result<Vec<u8>> {
+        if !self.abilities().camera_operations().contains(&CameraOperation::CapturePreview) {
+            return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_NOT_SUPPORTED));
+        }
+
+        let mut preview = MemoryMedia::new()?;
+
+        try_context!(context, ::gphoto2::gp_camera_capture_preview(self.camera, preview.as_mut_ptr(), context.as_mut_ptr()));
+
+        Ok(preview.to_vec())
+    }
+
     /// Downloads a file from the camera.
     pub fn download<T: Media>(&mut self, context: &mut Context, source: &CameraFile, destination: &mut T) -> ::Result<()> {
-        try_unsafe! {
+        self.download_as(context, source, FileType::Normal, destination)
+    }
+
+    /// Downloads a particular representation of a file from the camera.
+    ///
+    /// This can be used to retrieve only a file's thumbnail or EXIF data without transferring the
+    /// whole file. Which file types are available depends on the camera (see
+    /// `Abilities::file_operations()`).
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// let mut context = gphoto::Context::new().unwrap();
+    /// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+    /// let capture = camera.capture_image(&mut context).unwrap();
+    ///
+    /// let mut thumbnail = gphoto::MemoryMedia::new().unwrap();
+    /// camera.download_as(&mut context, &capture, gphoto::FileType::Preview, &mut thumbnail).unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the file could not be downloaded:
+    ///
+    /// * `NotSupported` if the camera can't provide the requested file type.
+    /// * `FileNotFound` if the file doesn't exist.
+    pub fn download_as<T: Media>(&mut self, context: &mut Context, source: &CameraFile, file_type: FileType, destination: &mut T) -> ::Result<()> {
+        try_context!(context, {
             ::gphoto2::gp_camera_file_get(self.camera,
                                           source.inner.folder.as_ptr(),
                                           source.inner.name.as_ptr(),
-                                          ::gphoto2::GP_FILE_TYPE_NORMAL,
+                                          file_type.as_libgphoto2(),
                                           destination.as_mut_ptr(),
                                           context.as_mut_ptr())
-        };
+        });
 
         Ok(())
     }
 
+    /// Lists the files in a folder on the camera's storage.
+    ///
+    /// The `folder` must be an absolute path, such as a storage's base directory. Subfolders are
+    /// not included; use `list_folders()` to find them.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// let mut context = gphoto::Context::new().unwrap();
+    /// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+    ///
+    /// for folder in camera.list_folders(&mut context, "/store_00010001/DCIM").unwrap() {
+    ///     for file in camera.list_files(&mut context, &folder).unwrap() {
+    ///         println!("{}/{}", file.directory(), file.basename());
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the folder could not be listed:
+    ///
+    /// * `DirectoryNotFound` if the folder doesn't exist.
+    /// * `PathNotAbsolute` if the folder is not an absolute path.
+    pub fn list_files(&mut self, context: &mut Context, folder: &str) -> ::Result<Vec<CameraFile>> {
+        let cfolder = util::path_to_cstring(folder)?;
+        let mut list = CameraList::new()?;
+
+        try_context!(context, ::gphoto2::gp_camera_folder_list_files(self.camera, cfolder.as_ptr(), list.as_mut_ptr(), context.as_mut_ptr()));
+
+        (0..list.len()).map(|i| util::file_path(folder.as_bytes(), list.name_bytes(i))).collect()
+    }
+
+    /// Lists the subfolders of a folder on the camera's storage.
+    ///
+    /// The `folder` must be an absolute path, such as a storage's base directory. The subfolders
+    /// are returned as absolute paths.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the folder could not be listed:
+    ///
+    /// * `DirectoryNotFound` if the folder doesn't exist.
+    /// * `PathNotAbsolute` if the folder is not an absolute path.
+    pub fn list_folders(&mut self, context: &mut Context, folder: &str) -> ::Result<Vec<String>> {
+        let cfolder = util::path_to_cstring(folder)?;
+        let mut list = CameraList::new()?;
+
+        try_context!(context, ::gphoto2::gp_camera_folder_list_folders(self.camera, cfolder.as_ptr(), list.as_mut_ptr(), context.as_mut_ptr()));
+
+        Ok((0..list.len()).map(|i| util::join_path(folder, &list.name(i))).collect())
+    }
+
+    /// Uploads a file to the camera.
+    ///
+    /// The contents of `source` are stored as a file named `name` inside `folder` on the camera's
+    /// storage.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    ///
+    /// let mut context = gphoto::Context::new().unwrap();
+    /// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+    /// let mut source = gphoto::FileMedia::open(Path::new("test.jpg")).unwrap();
+    ///
+    /// camera.upload(&mut context, "/store_00010001/DCIM/100NIKON", "TEST.JPG", &mut source, gphoto::FileType::Normal).unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the file could not be uploaded:
+    ///
+    /// * `ReadOnlyStorage` if the storage containing `folder` doesn't allow writing.
+    /// * `NotSupported` if the camera can't upload files.
+    /// * `FileExists` if the file already exists.
+    /// * `DirectoryNotFound` if the folder doesn't exist.
+    /// * `NoSpace` if there is not enough space on the storage.
+    pub fn upload<T: Media>(&mut self, context: &mut Context, folder: &str, name: &str, source: &mut T, file_type: FileType) -> ::Result<()> {
+        let cfolder = util::path_to_cstring(folder)?;
+        let cname = util::path_to_cstring(name)?;
+
+        // Cameras that don't report storage information are left to reject the upload themselves.
+        if let Ok(storage) = self.storage(context) {
+            let access = storage.iter()
+                                .filter(|s| s.base_dir().is_some_and(|base| util::path_contains(&base, folder)))
+                                .max_by_key(|s| s.base_dir().map_or(0, |base| base.len()))
+                                .and_then(|s| s.access_type());
+
+            match access {
+                Some(AccessType::ReadOnly) | Some(AccessType::ReadDelete) => {
+                    return Err(::error::from_libgphoto2(::error::ERROR_READ_ONLY_STORAGE));
+                },
+                _ => ()
+            }
+        }
+
+        try_context!(context, {
+            ::gphoto2::gp_camera_folder_put_file(self.camera,
+                                                 cfolder.as_ptr(),
+                                                 cname.as_ptr(),
+                                                 file_type.as_libgphoto2(),
+                                                 source.as_mut_ptr(),
+                                                 context.as_mut_ptr())
+        });
+
+        Ok(())
+    }
+
+    /// Deletes a file from the camera's storage.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the file could not be deleted:
+    ///
+    /// * `NotSupported` if the camera can't delete files.
+    /// * `FileNotFound` if the file doesn't exist.
+    pub fn delete_file(&mut self, context: &mut Context, file: &CameraFile) -> ::Result<()> {
+        try_context!(context, {
+            ::gphoto2::gp_camera_file_delete(self.camera,
+                                             file.inner.folder.as_ptr(),
+                                             file.inner.name.as_ptr(),
+                                             context.as_mut_ptr())
+        });
+
+        Ok(())
+    }
+
+    /// Deletes all files in a folder on the camera's storage.
+    ///
+    /// Subfolders and the files inside them are not deleted.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the files could not be deleted:
+    ///
+    /// * `NotSupported` if the camera can't delete files.
+    /// * `DirectoryNotFound` if the folder doesn't exist.
+    pub fn delete_all_in(&mut self, context: &mut Context, folder: &str) -> ::Result<()> {
+        let cfolder = util::path_to_cstring(folder)?;
+
+        try_context!(context, ::gphoto2::gp_camera_folder_delete_all(self.camera, cfolder.as_ptr(), context.as_mut_ptr()));
+
+        Ok(())
+    }
+
+    /// Creates a new folder named `name` inside the folder `parent` on the camera's storage.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the folder could not be created:
+    ///
+    /// * `NotSupported` if the camera can't create folders.
+    /// * `DirectoryExists` if the folder already exists.
+    /// * `DirectoryNotFound` if the parent folder doesn't exist.
+    pub fn make_dir(&mut self, context: &mut Context, parent: &str, name: &str) -> ::Result<()> {
+        let cparent = util::path_to_cstring(parent)?;
+        let cname = util::path_to_cstring(name)?;
+
+        try_context!(context, ::gphoto2::gp_camera_folder_make_dir(self.camera, cparent.as_ptr(), cname.as_ptr(), context.as_mut_ptr()));
+
+        Ok(())
+    }
+
+    /// Removes the folder named `name` from the folder `parent` on the camera's storage.
+    ///
+    /// Most cameras only allow removing empty folders.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the folder could not be removed:
+    ///
+    /// * `NotSupported` if the camera can't remove folders.
+    /// * `DirectoryNotFound` if the folder doesn't exist.
+    pub fn remove_dir(&mut self, context: &mut Context, parent: &str, name: &str) -> ::Result<()> {
+        let cparent = util::path_to_cstring(parent)?;
+        let cname = util::path_to_cstring(name)?;
+
+        try_context!(context, ::gphoto2::gp_camera_folder_remove_dir(self.camera, cparent.as_ptr(), cname.as_ptr(), context.as_mut_ptr()));
+
+        Ok(())
+    }
+
+    /// Returns an iterator over all files below a folder on the camera's storage.
+    ///
+    /// The walk starts at `root`, which must be an absolute path such as a storage's base
+    /// directory, and descends into all subfolders. See `Walk` for options that limit the walk.
+    pub fn walk<'a>(&'a mut self, context: &'a mut Context, root: &str) -> Walk<'a> {
+        ::walk::new(self, context, root)
+    }
+
+    /// Retrieves information about a file on the camera's storage.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the information could not be retrieved:
+    ///
+    /// * `FileNotFound` if the file doesn't exist.
+    /// * `DirectoryNotFound` if the file's directory doesn't exist.
+    pub fn file_info(&mut self, context: &mut Context, file: &CameraFile) -> ::Result<FileInfo> {
+        let mut info = unsafe { mem::zeroed() };
+
+        try_context!(context, {
+            ::gphoto2::gp_camera_file_get_info(self.camera,
+                                               file.inner.folder.as_ptr(),
+                                               file.inner.name.as_ptr(),
+                                               &mut info,
+                                               context.as_mut_ptr())
+        });
+
+        Ok(::file_info::from_libgphoto2(info))
+    }
+
     /// Returns information about the port the camera is connected to.
     pub fn port<'a>(&'a self) -> Port<'a> {
         let mut ptr = unsafe { mem::uninitialized() };
@@ -91,20 +652,178 @@ impl Camera {
     ///
     /// Returns a `Vec` containing one `Storage` for each filesystem on the device.
     pub fn storage(&mut self, context: &mut Context) -> ::Result<Vec<Storage>> {
-        let mut ptr = unsafe { mem::uninitialized() };
-        let mut len = unsafe { mem::uninitialized() };
+        let mut ptr = ptr::null_mut();
+        let mut len = 0;
 
-        try_unsafe! {
+        try_context!(context, {
             ::gphoto2::gp_camera_get_storageinfo(self.camera,
                                                  &mut ptr,
                                                  &mut len,
                                                  context.as_mut_ptr())
+        });
+
+        // The array is allocated by libgphoto2 with malloc(), so the entries are copied into owned
+        // values before the array is released with free().
+        let storage = if ptr.is_null() {
+            Vec::new()
+        }
+        else {
+            unsafe { slice::from_raw_parts(ptr, len as usize) }.iter().map(::storage::from_libgphoto2).collect()
         };
 
-        let storage = ptr as *mut Storage;
-        let length = len as usize;
+        unsafe {
+            ::libc::free(ptr as *mut c_void);
+        }
 
-        Ok(unsafe { Vec::from_raw_parts(storage, length, length) })
+        Ok(storage)
+    }
+
+    /// Retrieves the camera's configuration.
+    ///
+    /// Returns the root of the camera's configuration tree, which is a widget of type `Window`.
+    /// Changes made to the tree are not applied to the camera until the tree is passed to
+    /// `set_config()`.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the configuration could not be retrieved:
+    ///
+    /// * `NotSupported` if the camera can not be configured.
+    pub fn config(&mut self, context: &mut Context) -> ::Result<Widget> {
+        let mut ptr = ptr::null_mut();
+
+        try_context!(context, ::gphoto2::gp_camera_get_config(self.camera, &mut ptr, context.as_mut_ptr()));
+
+        Ok(::widget::from_libgphoto2(ptr))
+    }
+
+    /// Applies a configuration tree to the camera.
+    ///
+    /// The `config` widget should be the root of a tree returned by `config()`. Only the widgets
+    /// whose values were changed are applied.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the configuration could not be applied:
+    ///
+    /// * `NotSupported` if the camera can not be configured.
+    /// * `InvalidInput` if a changed value is not accepted by the camera.
+    pub fn set_config(&mut self, context: &mut Context, config: &mut Widget) -> ::Result<()> {
+        try_context!(context, ::gphoto2::gp_camera_set_config(self.camera, config.as_mut_ptr(), context.as_mut_ptr()));
+
+        Ok(())
+    }
+
+    /// Retrieves a single configuration widget by its name.
+    ///
+    /// The `name` is the short name of the setting, e.g., `"iso"` or `"shutterspeed"`. Drivers
+    /// that can't look up single settings fall back to searching the full configuration tree.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the widget could not be retrieved:
+    ///
+    /// * `SettingNotFound` if the camera has no setting with the given name.
+    /// * `NotSupported` if the camera can not be configured.
+    pub fn config_widget(&mut self, context: &mut Context, name: &str) -> ::Result<Widget> {
+        let cname = util::setting_name(name)?;
+        let mut ptr = ptr::null_mut();
+
+        ::context::clear_messages(context);
+
+        match unsafe { ffi::gp_camera_get_single_config(self.camera, cname.as_ptr(), &mut ptr, context.as_mut_ptr()) } {
+            ::gphoto2::GP_OK => Ok(::widget::from_libgphoto2(ptr)),
+            ::gphoto2::GP_ERROR_NOT_SUPPORTED => {
+                let config = self.config(context)?;
+
+                config.child_by_name(name).ok_or_else(|| {
+                    ::error::from_libgphoto2(::error::ERROR_SETTING_NOT_FOUND)
+                })
+            },
+            ::gphoto2::GP_ERROR_BAD_PARAMETERS => Err(::error::from_libgphoto2(::error::ERROR_SETTING_NOT_FOUND)),
+            err => Err(::context::error(context, err))
+        }
+    }
+
+    /// Returns the value of a single configuration setting.
+    ///
+    /// Returns `None` if the setting is a widget that doesn't hold a value.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// let mut context = gphoto::Context::new().unwrap();
+    /// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+    ///
+    /// println!("iso = {:?}", camera.config_value(&mut context, "iso").unwrap());
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the value could not be retrieved:
+    ///
+    /// * `SettingNotFound` if the camera has no setting with the given name.
+    /// * `NotSupported` if the camera can not be configured.
+    /// * `CorruptedData` if a text value is invalid UTF-8.
+    pub fn config_value(&mut self, context: &mut Context, name: &str) -> ::Result<Option<WidgetValue>> {
+        self.config_widget(context, name)?.value()
+    }
+
+    /// Changes the value of a single configuration setting.
+    ///
+    /// The value is given in its textual representation and is interpreted according to the type
+    /// of the setting's widget (see `Widget::set_value_from_str()`). Drivers that can't change
+    /// single settings fall back to applying the full configuration tree.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// let mut context = gphoto::Context::new().unwrap();
+    /// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+    ///
+    /// camera.set_config_value(&mut context, "shutterspeed", "1/250").unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the value could not be changed:
+    ///
+    /// * `SettingNotFound` if the camera has no setting with the given name.
+    /// * `NotSupported` if the camera can not be configured.
+    /// * `InvalidInput` if the value is not valid for the setting.
+    pub fn set_config_value(&mut self, context: &mut Context, name: &str, value: &str) -> ::Result<()> {
+        let cname = util::setting_name(name)?;
+        let mut ptr = ptr::null_mut();
+
+        ::context::clear_messages(context);
+
+        match unsafe { ffi::gp_camera_get_single_config(self.camera, cname.as_ptr(), &mut ptr, context.as_mut_ptr()) } {
+            ::gphoto2::GP_OK => {
+                let mut widget = ::widget::from_libgphoto2(ptr);
+                widget.set_value_from_str(value)?;
+
+                match unsafe { ffi::gp_camera_set_single_config(self.camera, cname.as_ptr(), widget.as_mut_ptr(), context.as_mut_ptr()) } {
+                    ::gphoto2::GP_OK => return Ok(()),
+                    ::gphoto2::GP_ERROR_NOT_SUPPORTED => (),
+                    err => return Err(::context::error(context, err))
+                }
+            },
+            ::gphoto2::GP_ERROR_NOT_SUPPORTED => (),
+            ::gphoto2::GP_ERROR_BAD_PARAMETERS => return Err(::error::from_libgphoto2(::error::ERROR_SETTING_NOT_FOUND)),
+            err => return Err(::context::error(context, err))
+        }
+
+        let mut config = self.config(context)?;
+
+        {
+            let mut widget = config.child_by_name(name).ok_or_else(|| {
+                ::error::from_libgphoto2(::error::ERROR_SETTING_NOT_FOUND)
+            })?;
+
+            widget.set_value_from_str(value)?;
+        }
+
+        self.set_config(context, &mut config)
     }
 
     /// Returns the camera's summary.
@@ -121,7 +840,7 @@ impl Camera {
     pub fn summary(&mut self, context: &mut Context) -> ::Result<String> {
         let mut summary = unsafe { mem::uninitialized() };
 
-        try_unsafe!(::gphoto2::gp_camera_get_summary(self.camera, &mut summary, context.as_mut_ptr()));
+        try_context!(context, ::gphoto2::gp_camera_get_summary(self.camera, &mut summary, context.as_mut_ptr()));
 
         util::camera_text_to_string(summary)
     }
@@ -139,7 +858,7 @@ impl Camera {
     pub fn manual(&mut self, context: &mut Context) -> ::Result<String> {
         let mut manual = unsafe { mem::uninitialized() };
 
-        try_unsafe!(::gphoto2::gp_camera_get_manual(self.camera, &mut manual, context.as_mut_ptr()));
+        try_context!(context, ::gphoto2::gp_camera_get_manual(self.camera, &mut manual, context.as_mut_ptr()));
 
         util::camera_text_to_string(manual)
     }
@@ -157,7 +876,7 @@ impl Camera {
     pub fn about_driver(&mut self, context: &mut Context) -> ::Result<String> {
         let mut about = unsafe { mem::uninitialized() };
 
-        try_unsafe!(::gphoto2::gp_camera_get_about(self.camera, &mut about, context.as_mut_ptr()));
+        try_context!(context, ::gphoto2::gp_camera_get_about(self.camera, &mut about, context.as_mut_ptr()));
 
         util::camera_text_to_string(about)
     }
@@ -185,8 +904,274 @@ impl CameraFile {
     }
 }
 
+impl Clone for CameraFile {
+    fn clone(&self) -> Self {
+        CameraFile {
+            inner: ::gphoto2::CameraFilePath {
+                name: self.inner.name,
+                folder: self.inner.folder,
+            },
+        }
+    }
+}
+
+impl fmt::Debug for CameraFile {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("CameraFile")
+           .field("directory", &self.directory())
+           .field("basename", &self.basename())
+           .finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for CameraFile {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok,S::Error>
+        where S: ::serde::Serializer
+    {
+        use ::serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("CameraFile", 2)?;
+        state.serialize_field("directory", &self.directory())?;
+        state.serialize_field("basename", &self.basename())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for CameraFile {
+    fn deserialize<D>(deserializer: D) -> Result<Self,D::Error>
+        where D: ::serde::Deserializer<'de>
+    {
+        #[derive(Deserialize)]
+        #[serde(rename = "CameraFile")]
+        struct Path {
+            directory: String,
+            basename: String,
+        }
+
+        let path: Path = ::serde::Deserialize::deserialize(deserializer)?;
+
+        util::file_path(path.directory.as_bytes(), path.basename.as_bytes()).map_err(::serde::de::Error::custom)
+    }
+}
+
+/// Types of media that a camera can capture.
+#[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub enum CaptureType {
+    /// A still image.
+    Image,
+
+    /// A movie.
+    Movie,
+
+    /// A sound clip.
+    Sound,
+}
+
+impl CaptureType {
+    fn as_libgphoto2(&self) -> ::gphoto2::CameraCaptureType {
+        match *self {
+            CaptureType::Image => ::gphoto2::GP_CAPTURE_IMAGE,
+            CaptureType::Movie => ::gphoto2::GP_CAPTURE_MOVIE,
+            CaptureType::Sound => ::gphoto2::GP_CAPTURE_SOUND,
+        }
+    }
+}
+
+/// Representations of a file that can be transferred to or from a camera.
+#[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub enum FileType {
+    /// The file as stored on the camera.
+    Normal,
+
+    /// A preview (thumbnail) of the file.
+    Preview,
+
+    /// Raw data of the file.
+    Raw,
+
+    /// Audio data attached to the file.
+    Audio,
+
+    /// EXIF data of the file.
+    EXIF,
+
+    /// Metadata of the file, as defined by the camera driver.
+    Metadata,
+}
+
+impl FileType {
+    fn as_libgphoto2(&self) -> ::gphoto2::CameraFileType {
+        match *self {
+            FileType::Normal   => ::gphoto2::GP_FILE_TYPE_NORMAL,
+            FileType::Preview  => ::gphoto2::GP_FILE_TYPE_PREVIEW,
+            FileType::Raw      => ::gphoto2::GP_FILE_TYPE_RAW,
+            FileType::Audio    => ::gphoto2::GP_FILE_TYPE_AUDIO,
+            FileType::EXIF     => ::gphoto2::GP_FILE_TYPE_EXIF,
+            FileType::Metadata => ::gphoto2::GP_FILE_TYPE_METADATA,
+        }
+    }
+}
+
+/// Events reported by a camera.
+#[derive(Debug)]
+pub enum CameraEvent {
+    /// No event was reported before the timeout expired.
+    Timeout,
+
+    /// A file was added to the camera's storage, e.g., as the result of a capture.
+    FileAdded(CameraFile),
+
+    /// A folder was added to the camera's storage.
+    ///
+    /// The value is the absolute path of the new folder.
+    FolderAdded(String),
+
+    /// A capture that was triggered on the camera has completed.
+    CaptureComplete,
+
+    /// A file on the camera's storage was changed.
+    FileChanged(CameraFile),
+
+    /// An event that is not otherwise understood.
+    ///
+    /// The value is the event's description as reported by the driver.
+    Unknown(String),
+}
+
 mod util {
-    use std::ffi::CStr;
+    use std::ffi::{CStr,CString};
+    use std::mem;
+    use std::ptr;
+    use std::thread;
+    use std::time::Duration;
+
+    use ::libc::{c_char,c_void};
+
+    pub unsafe fn read_file_path(ptr: *mut c_void) -> super::CameraFile {
+        super::CameraFile { inner: ptr::read(ptr as *const ::gphoto2::CameraFilePath) }
+    }
+
+    pub fn init_with_policy<F>(context: &mut ::context::Context, policy: ::claim::ClaimPolicy, mut new_camera: F) -> ::Result<super::Camera>
+        where F: FnMut(&mut ::context::Context) -> ::Result<super::Camera>
+    {
+        let (mut retries, mut delay) = match policy {
+            ::claim::ClaimPolicy::Fail => (0, Duration::from_secs(0)),
+            ::claim::ClaimPolicy::Retry { attempts, delay } => (attempts, delay),
+        };
+
+        loop {
+            let mut camera = new_camera(context)?;
+
+            match camera.init(context) {
+                Ok(()) => return Ok(camera),
+                Err(ref err) if err.kind() == ::error::ErrorKind::UsbClaim && retries > 0 => {
+                    // The failed camera still holds the port, so it's released before waiting for
+                    // the other process to let go of the device.
+                    drop(camera);
+                    thread::sleep(delay);
+
+                    retries -= 1;
+                    delay = delay.checked_mul(2).unwrap_or(delay);
+                },
+                Err(err) => {
+                    if err.kind() == ::error::ErrorKind::UsbClaim {
+                        let port_path = camera.port().path().into_owned();
+
+                        // libusb has already opened the device node on behalf of the failed
+                        // camera, so it's released before looking for the processes holding it.
+                        drop(camera);
+
+                        let claimants = ::claim::find_claimants(&port_path);
+                        return Err(::error::with_claimants(err, claimants));
+                    }
+
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    pub fn set_abilities(camera: *mut ::gphoto2::Camera, context: &mut ::context::Context, model: &str) -> ::Result<()> {
+        let list = ::abilities::AbilitiesList::load(context)?;
+
+        let abilities = match list.find_model(model) {
+            Some(abilities) => abilities,
+            None => return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_MODEL_NOT_FOUND))
+        };
+
+        try_unsafe!(::gphoto2::gp_camera_set_abilities(camera, ptr::read(::abilities::as_libgphoto2(&abilities))));
+
+        Ok(())
+    }
+
+    pub fn set_port_info(camera: *mut ::gphoto2::Camera, path: &str) -> ::Result<()> {
+        let list = ::port::PortInfoList::load()?;
+
+        let info = match ::port::info_by_path(&list, path) {
+            Some(info) => info,
+            None => return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_UNKNOWN_PORT))
+        };
+
+        try_unsafe!(::gphoto2::gp_camera_set_port_info(camera, info));
+
+        Ok(())
+    }
+
+    pub fn path_to_cstring(path: &str) -> ::Result<CString> {
+        CString::new(path).map_err(|_| {
+            ::error::from_libgphoto2(::gphoto2::GP_ERROR_BAD_PARAMETERS)
+        })
+    }
+
+    pub fn file_path(directory: &[u8], name: &[u8]) -> ::Result<super::CameraFile> {
+        let mut inner: ::gphoto2::CameraFilePath = unsafe { mem::zeroed() };
+
+        if directory.len() >= inner.folder.len() || name.len() >= inner.name.len() {
+            return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_BAD_PARAMETERS));
+        }
+
+        for (dst, &src) in inner.folder.iter_mut().zip(directory) {
+            *dst = src as c_char;
+        }
+
+        for (dst, &src) in inner.name.iter_mut().zip(name) {
+            *dst = src as c_char;
+        }
+
+        Ok(super::CameraFile { inner: inner })
+    }
+
+    pub fn ignore_missing_setting(result: ::Result<()>) -> ::Result<()> {
+        match result {
+            Err(ref err) if err.kind() == ::ErrorKind::SettingNotFound => Ok(()),
+            result => result
+        }
+    }
+
+    pub fn path_contains(directory: &str, path: &str) -> bool {
+        let directory = directory.trim_end_matches('/');
+
+        path == directory || (path.starts_with(directory) && path[directory.len()..].starts_with('/'))
+    }
+
+    pub fn join_path(directory: &str, name: &str) -> String {
+        if directory.ends_with('/') {
+            format!("{}{}", directory, name)
+        }
+        else {
+            format!("{}/{}", directory, name)
+        }
+    }
+
+    pub fn setting_name(name: &str) -> ::Result<CString> {
+        CString::new(name).map_err(|_| {
+            ::error::from_libgphoto2(::error::ERROR_SETTING_NOT_FOUND)
+        })
+    }
 
     pub fn camera_text_to_string(mut camera_text: ::gphoto2::CameraText) -> ::Result<String> {
         let length = unsafe {
@@ -202,3 +1187,66 @@ mod util {
         })
     }
 }
+
+mod ffi {
+    use ::libc::{c_char,c_int,c_void};
+
+    pub const GP_EVENT_UNKNOWN:          c_int = 0;
+    pub const GP_EVENT_TIMEOUT:          c_int = 1;
+    pub const GP_EVENT_FILE_ADDED:       c_int = 2;
+    pub const GP_EVENT_FOLDER_ADDED:     c_int = 3;
+    pub const GP_EVENT_CAPTURE_COMPLETE: c_int = 4;
+    pub const GP_EVENT_FILE_CHANGED:     c_int = 5;
+
+    extern "C" {
+        // Redeclared because the declaration in `gphoto2-sys` writes the event type into its
+        // `CameraEventType` enum, which has no variant for `GP_EVENT_FILE_CHANGED` (5). Receiving
+        // that event would store an invalid enum value, so the type is received as a plain integer.
+        pub fn gp_camera_wait_for_event(camera: *mut ::gphoto2::Camera, timeout: c_int, event_type: *mut c_int, event_data: *mut *mut c_void, context: *mut ::gphoto2::GPContext) -> c_int;
+
+        // Not exported by `gphoto2-sys`.
+        pub fn gp_camera_get_single_config(camera: *mut ::gphoto2::Camera, name: *const c_char, widget: *mut *mut ::gphoto2::CameraWidget, context: *mut ::gphoto2::GPContext) -> c_int;
+        pub fn gp_camera_set_single_config(camera: *mut ::gphoto2::Camera, name: *const c_char, widget: *mut ::gphoto2::CameraWidget, context: *mut ::gphoto2::GPContext) -> c_int;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::util::{join_path,path_contains};
+
+    #[test]
+    fn path_contains_same_directory() {
+        assert!(path_contains("/store_00010001/DCIM", "/store_00010001/DCIM"));
+        assert!(path_contains("/store_00010001/DCIM/", "/store_00010001/DCIM"));
+    }
+
+    #[test]
+    fn path_contains_subdirectories() {
+        assert!(path_contains("/store_00010001", "/store_00010001/DCIM"));
+        assert!(path_contains("/store_00010001/", "/store_00010001/DCIM/100CANON"));
+    }
+
+    #[test]
+    fn path_contains_everything_under_root() {
+        assert!(path_contains("/", "/"));
+        assert!(path_contains("/", "/store_00010001"));
+    }
+
+    #[test]
+    fn path_contains_rejects_sibling_with_common_prefix() {
+        assert!(!path_contains("/a/b", "/a/bc"));
+        assert!(!path_contains("/a/b/", "/a/bc"));
+        assert!(!path_contains("/a/b", "/a"));
+    }
+
+    #[test]
+    fn join_path_adds_separator() {
+        assert_eq!("/store_00010001/DCIM", join_path("/store_00010001", "DCIM"));
+    }
+
+    #[test]
+    fn join_path_keeps_single_separator() {
+        assert_eq!("/store_00010001/DCIM", join_path("/store_00010001/", "DCIM"));
+        assert_eq!("/store_00010001", join_path("/", "store_00010001"));
+    }
+}
@@ -0,0 +1,172 @@
+use std::fs::{self,File};
+use std::io::Read;
+use std::path::{Path,PathBuf};
+use std::process;
+use std::time::Duration;
+
+/// Policies for handling a camera that is claimed by another process.
+///
+/// Opening a camera fails with an error of kind `UsbClaim` when another process has already
+/// claimed the camera's USB device. On Linux desktops, this is commonly a volume monitor such as
+/// `gvfs-gphoto2-volume-monitor`, which opens cameras as soon as they are connected and releases
+/// them shortly afterwards.
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum ClaimPolicy {
+    /// Fail immediately.
+    Fail,
+
+    /// Retry opening the camera up to `attempts` more times.
+    ///
+    /// The first retry waits for `delay`, and the delay doubles with each following retry.
+    Retry {
+        /// Number of retries after the first attempt.
+        attempts: u32,
+
+        /// Delay before the first retry.
+        delay: Duration,
+    },
+}
+
+impl Default for ClaimPolicy {
+    fn default() -> Self {
+        ClaimPolicy::Fail
+    }
+}
+
+/// A process that holds a camera's USB device open.
+///
+/// When opening a camera fails with an error of kind `UsbClaim`, the processes holding the device
+/// are reported by `Error::claimants()`. Finding the processes is only supported on Linux, and
+/// only processes whose open files are visible to the current user are found.
+#[derive(Debug,PartialEq,Eq,Clone,Hash)]
+pub struct UsbClaimant {
+    pid: u32,
+    name: String,
+}
+
+impl UsbClaimant {
+    /// Returns the process ID.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Returns the name of the process, e.g., `"gvfs-gphoto2-vo"`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+
+/// Finds the processes that have the USB device for the port `port_path` open.
+///
+/// The port path has the form `"usb:BUS,DEVICE"`, which corresponds to the device node
+/// `/dev/bus/usb/BUS/DEVICE`.
+#[doc(hidden)]
+pub fn find_claimants(port_path: &str) -> Vec<UsbClaimant> {
+    let device = match util::usb_device_node(port_path) {
+        Some(device) => device,
+        None => return Vec::new()
+    };
+
+    let processes = match fs::read_dir("/proc") {
+        Ok(processes) => processes,
+        Err(_) => return Vec::new()
+    };
+
+    processes.filter_map(|entry| entry.ok()).filter_map(|entry| {
+        let pid = match entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) {
+            Some(pid) => pid,
+            None => return None
+        };
+
+        // The calling process may still have the device open from its own failed attempt.
+        if pid == process::id() {
+            return None;
+        }
+
+        if util::has_open_file(&entry.path().join("fd"), &device) {
+            Some(UsbClaimant {
+                pid: pid,
+                name: util::process_name(&entry.path()),
+            })
+        }
+        else {
+            None
+        }
+    }).collect()
+}
+
+mod util {
+    use super::*;
+
+    pub fn usb_device_node(port_path: &str) -> Option<PathBuf> {
+        if !port_path.starts_with("usb:") {
+            return None;
+        }
+
+        let mut parts = port_path["usb:".len()..].split(',');
+
+        let bus = parts.next().and_then(|s| s.parse::<u32>().ok());
+        let device = parts.next().and_then(|s| s.parse::<u32>().ok());
+
+        match (bus, device) {
+            (Some(bus), Some(device)) => Some(PathBuf::from(format!("/dev/bus/usb/{:03}/{:03}", bus, device))),
+            _ => None
+        }
+    }
+
+    pub fn has_open_file(fd_dir: &Path, file: &Path) -> bool {
+        match fs::read_dir(fd_dir) {
+            Ok(fds) => {
+                fds.filter_map(|fd| fd.ok()).any(|fd| {
+                    fs::read_link(fd.path()).map(|target| target == file).unwrap_or(false)
+                })
+            },
+            Err(_) => false
+        }
+    }
+
+    pub fn process_name(process_dir: &Path) -> String {
+        let mut name = String::new();
+
+        if let Ok(mut file) = File::open(process_dir.join("comm")) {
+            let _ = file.read_to_string(&mut name);
+        }
+
+        name.trim_end().to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::util::usb_device_node;
+
+    #[test]
+    fn usb_device_node_pads_bus_and_device() {
+        assert_eq!(Some(PathBuf::from("/dev/bus/usb/020/007")), usb_device_node("usb:020,007"));
+        assert_eq!(Some(PathBuf::from("/dev/bus/usb/001/012")), usb_device_node("usb:1,12"));
+    }
+
+    #[test]
+    fn usb_device_node_rejects_other_port_types() {
+        assert_eq!(None, usb_device_node("ptpip:192.168.1.1"));
+        assert_eq!(None, usb_device_node("disk:/media/card"));
+        assert_eq!(None, usb_device_node("020,007"));
+    }
+
+    #[test]
+    fn usb_device_node_requires_device_number() {
+        assert_eq!(None, usb_device_node("usb:020"));
+        assert_eq!(None, usb_device_node("usb:020,"));
+        assert_eq!(None, usb_device_node("usb:"));
+    }
+
+    #[test]
+    fn usb_device_node_rejects_non_numeric_parts() {
+        assert_eq!(None, usb_device_node("usb:abc,007"));
+        assert_eq!(None, usb_device_node("usb:020,xyz"));
+        assert_eq!(None, usb_device_node("usb:-1,007"));
+    }
+}
@@ -1,8 +1,136 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool,Ordering};
+
+use ::libc::{c_int,c_void};
+
 use ::handle::{Handle,HandleMut};
+use ::list::CameraList;
 
 /// A `libgphoto2` library context.
+///
+/// Besides being required by most operations, a context is how `libgphoto2` reports feedback
+/// about long-running operations. Callbacks can be registered to receive progress updates,
+/// status messages, error messages, and messages intended for the user.
+///
+/// ## Example
+///
+/// ```no_run
+/// let mut context = gphoto::Context::new().unwrap();
+///
+/// context.set_status_func(|status| println!("status: {}", status));
+/// context.set_error_func(|error| println!("error: {}", error));
+/// ```
+///
+/// ## Threads
+///
+/// A context can be moved to another thread, so its callbacks must be `Send`. It can't be shared
+/// between threads, because every operation requires exclusive access to the context. Threads
+/// that operate the same camera should each use their own context.
 pub struct Context {
     context: *mut ::gphoto2::GPContext,
+    callbacks: *mut Callbacks,
+}
+
+// `libgphoto2` doesn't tie a context to the thread that created it, and the callbacks that the
+// context owns through its raw pointer are all required to be `Send`. See also "Threads" in the
+// documentation of `Camera`.
+unsafe impl Send for Context {}
+
+/// A trait for types that report the progress of long-running operations.
+///
+/// Operations such as downloading a file report their progress by starting a progress
+/// indicator, updating it as the operation proceeds, and stopping it when the operation is
+/// finished. Progress indicators may be nested, so each one is identified by an `id`.
+pub trait Progress {
+    /// Called when an operation starts.
+    ///
+    /// The operation is complete when its progress reaches `target`. The `message` describes the
+    /// operation.
+    fn start(&mut self, id: u32, target: f32, message: &str);
+
+    /// Called when an operation makes progress.
+    ///
+    /// The `current` value is the operation's progress towards the `target` that was given to
+    /// `start()`.
+    fn update(&mut self, id: u32, current: f32);
+
+    /// Called when an operation stops.
+    fn stop(&mut self, id: u32);
+}
+
+type Callback = Box<dyn FnMut(&str) + Send>;
+
+struct Callbacks {
+    progress: Option<Box<dyn Progress + Send>>,
+    next_progress_id: u32,
+    status: Option<Callback>,
+    error: Option<Callback>,
+    message: Option<Callback>,
+    cancel: Option<CancelToken>,
+    last_error: Option<String>,
+    last_status: Option<String>,
+}
+
+/// A token that can be used to cancel long-running operations.
+///
+/// A `CancelToken` is attached to a `Context` with `Context::set_cancel_token()`. Calling
+/// `cancel()` on the token or any of its clones causes operations using the context to stop at the
+/// next opportunity and return an error of kind `Cancel`. Cancellation can be requested from any
+/// thread.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// let mut context = gphoto::Context::new().unwrap();
+/// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+///
+/// let token = gphoto::CancelToken::new();
+/// context.set_cancel_token(token.clone());
+///
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_secs(1));
+///     token.cancel();
+/// });
+///
+/// match camera.capture_image(&mut context) {
+///     Err(ref err) if err.kind() == gphoto::ErrorKind::Cancel => println!("canceled"),
+///     result => println!("{:?}", result.map(|file| file.basename().into_owned())),
+/// }
+/// ```
+#[derive(Debug,Clone)]
+pub struct CancelToken {
+    canceled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Creates a new token that has not been canceled.
+    pub fn new() -> Self {
+        CancelToken { canceled: Arc::new(AtomicBool::new(false)) }
+    }
+
+    /// Requests cancellation of the operations using this token.
+    pub fn cancel(&self) {
+        self.canceled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if cancellation has been requested.
+    pub fn is_canceled(&self) -> bool {
+        self.canceled.load(Ordering::SeqCst)
+    }
+
+    /// Clears a previous cancellation request so that the token can be reused.
+    pub fn reset(&self) {
+        self.canceled.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        CancelToken::new()
+    }
 }
 
 impl Context {
@@ -11,18 +139,138 @@ impl Context {
         let ptr = unsafe { ::gphoto2::gp_context_new() };
 
         if !ptr.is_null() {
-            Ok(Context { context: ptr })
+            let mut context = Context {
+                context: ptr,
+                callbacks: Box::into_raw(Box::new(Callbacks {
+                    progress: None,
+                    next_progress_id: 0,
+                    status: None,
+                    error: None,
+                    message: None,
+                    cancel: None,
+                    last_error: None,
+                    last_status: None,
+                })),
+            };
+
+            // Error and status messages are always recorded so that they can be attached to errors.
+            unsafe {
+                ::gphoto2::gp_context_set_error_func(context.context, callbacks::error, context.callbacks_ptr());
+                ::gphoto2::gp_context_set_status_func(context.context, callbacks::status, context.callbacks_ptr());
+            }
+
+            Ok(context)
         }
         else {
             Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_NO_MEMORY))
         }
     }
+
+    /// Registers an object that receives progress updates.
+    ///
+    /// Replaces any previously registered progress object. The callbacks are called on the thread
+    /// that performs the operation and must not panic.
+    pub fn set_progress<P: Progress + Send + 'static>(&mut self, progress: P) {
+        unsafe {
+            (*self.callbacks).progress = Some(Box::new(progress));
+        }
+
+        unsafe {
+            ::gphoto2::gp_context_set_progress_funcs(self.context,
+                                                     callbacks::progress_start,
+                                                     callbacks::progress_update,
+                                                     callbacks::progress_stop,
+                                                     self.callbacks_ptr());
+        }
+    }
+
+    /// Registers a closure that receives status messages.
+    ///
+    /// Status messages describe what `libgphoto2` is currently doing, e.g., `"Downloading
+    /// 'DSC_0001.JPG'..."`. Replaces any previously registered status closure. The closure must
+    /// not panic.
+    pub fn set_status_func<F: FnMut(&str) + Send + 'static>(&mut self, func: F) {
+        unsafe {
+            (*self.callbacks).status = Some(Box::new(func));
+        }
+    }
+
+    /// Registers a closure that receives error messages.
+    ///
+    /// Error messages explain the cause of a failed operation in more detail than the error code
+    /// that is returned. Replaces any previously registered error closure. The closure must not
+    /// panic.
+    pub fn set_error_func<F: FnMut(&str) + Send + 'static>(&mut self, func: F) {
+        unsafe {
+            (*self.callbacks).error = Some(Box::new(func));
+        }
+    }
+
+    /// Registers a closure that receives messages intended for the user.
+    ///
+    /// Replaces any previously registered message closure. The closure must not panic.
+    pub fn set_message_func<F: FnMut(&str) + Send + 'static>(&mut self, func: F) {
+        unsafe {
+            (*self.callbacks).message = Some(Box::new(func));
+        }
+
+        unsafe {
+            ::gphoto2::gp_context_set_message_func(self.context, callbacks::message, self.callbacks_ptr());
+        }
+    }
+
+    /// Attaches a token that can be used to cancel operations using this context.
+    ///
+    /// Replaces any previously attached token.
+    pub fn set_cancel_token(&mut self, token: CancelToken) {
+        unsafe {
+            (*self.callbacks).cancel = Some(token);
+        }
+
+        unsafe {
+            ::gphoto2::gp_context_set_cancel_func(self.context, callbacks::cancel, self.callbacks_ptr());
+        }
+    }
+
+    fn callbacks_ptr(&mut self) -> *mut c_void {
+        self.callbacks as *mut c_void
+    }
+
+    /// Detects all cameras connected to the system.
+    ///
+    /// Returns a `Vec` containing the model name and port path of each detected camera. Pass both
+    /// values to `Camera::open()` to open a particular camera.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// let mut context = gphoto::Context::new().unwrap();
+    ///
+    /// for (model, port) in context.detect_cameras().unwrap() {
+    ///     println!("{} on {}", model, port);
+    /// }
+    /// ```
+    ///
+    /// The above example may print something like the following:
+    ///
+    /// ```text
+    /// Nikon DSC D750 on usb:020,007
+    /// Nikon DSC D750 on usb:020,009
+    /// ```
+    pub fn detect_cameras(&mut self) -> ::Result<Vec<(String,String)>> {
+        let mut list = CameraList::new()?;
+
+        try_context!(self, ::gphoto2::gp_camera_autodetect(list.as_mut_ptr(), self.context));
+
+        Ok((0..list.len()).map(|i| (list.name(i).into_owned(), list.value(i).into_owned())).collect())
+    }
 }
 
 impl Drop for Context {
     fn drop(&mut self) {
         unsafe {
             ::gphoto2::gp_context_unref(self.context);
+            drop(Box::from_raw(self.callbacks));
         }
     }
 }
@@ -40,3 +288,100 @@ impl HandleMut<::gphoto2::GPContext> for Context {
         self.context
     }
 }
+
+/// Forgets the messages recorded by previous operations.
+#[doc(hidden)]
+pub fn clear_messages(context: &mut Context) {
+    let callbacks = unsafe { &mut *context.callbacks };
+
+    callbacks.last_error = None;
+    callbacks.last_status = None;
+}
+
+/// Creates an error with the messages recorded since the last call to `clear_messages()`.
+#[doc(hidden)]
+pub fn error(context: &mut Context, err: c_int) -> ::Error {
+    let callbacks = unsafe { &mut *context.callbacks };
+    let detail = callbacks.last_error.take().or_else(|| callbacks.last_status.take());
+
+    ::error::from_libgphoto2_with_detail(err, detail)
+}
+
+// The callbacks receive the context's pointer to its `Callbacks`, which is allocated separately so
+// that its address doesn't change when the `Context` is moved. The `Context` only accesses the
+// callbacks through the same pointer, so the references created here don't alias a `Box`.
+mod callbacks {
+    use ::libc::{c_char,c_float,c_uint,c_void};
+
+    use ::list::util::lossy_str;
+
+    use super::Callbacks;
+
+    pub extern "C" fn progress_start(_context: *mut ::gphoto2::GPContext, target: c_float, message: *const c_char, data: *mut c_void) -> c_uint {
+        let callbacks = unsafe { &mut *(data as *mut Callbacks) };
+
+        let id = callbacks.next_progress_id;
+        callbacks.next_progress_id = id.wrapping_add(1);
+
+        if let Some(ref mut progress) = callbacks.progress {
+            progress.start(id, target, &unsafe { lossy_str(message) });
+        }
+
+        id
+    }
+
+    pub extern "C" fn progress_update(_context: *mut ::gphoto2::GPContext, id: c_uint, current: c_float, data: *mut c_void) {
+        let callbacks = unsafe { &mut *(data as *mut Callbacks) };
+
+        if let Some(ref mut progress) = callbacks.progress {
+            progress.update(id, current);
+        }
+    }
+
+    pub extern "C" fn progress_stop(_context: *mut ::gphoto2::GPContext, id: c_uint, data: *mut c_void) {
+        let callbacks = unsafe { &mut *(data as *mut Callbacks) };
+
+        if let Some(ref mut progress) = callbacks.progress {
+            progress.stop(id);
+        }
+    }
+
+    pub extern "C" fn status(_context: *mut ::gphoto2::GPContext, message: *const c_char, data: *mut c_void) {
+        let callbacks = unsafe { &mut *(data as *mut Callbacks) };
+        let message = unsafe { lossy_str(message) };
+
+        if let Some(ref mut func) = callbacks.status {
+            func(&message);
+        }
+
+        callbacks.last_status = Some(message.into_owned());
+    }
+
+    pub extern "C" fn error(_context: *mut ::gphoto2::GPContext, message: *const c_char, data: *mut c_void) {
+        let callbacks = unsafe { &mut *(data as *mut Callbacks) };
+        let message = unsafe { lossy_str(message) };
+
+        if let Some(ref mut func) = callbacks.error {
+            func(&message);
+        }
+
+        callbacks.last_error = Some(message.into_owned());
+    }
+
+    pub extern "C" fn message(_context: *mut ::gphoto2::GPContext, message: *const c_char, data: *mut c_void) {
+        let callbacks = unsafe { &mut *(data as *mut Callbacks) };
+
+        if let Some(ref mut func) = callbacks.message {
+            func(&unsafe { lossy_str(message) });
+        }
+    }
+
+    pub extern "C" fn cancel(_context: *mut ::gphoto2::GPContext, data: *mut c_void) -> ::gphoto2::GPContextFeedback {
+        let callbacks = unsafe { &*(data as *const Callbacks) };
+
+        match callbacks.cancel {
+            Some(ref token) if token.is_canceled() => ::gphoto2::GP_CONTEXT_FEEDBACK_CANCEL,
+            _ => ::gphoto2::GP_CONTEXT_FEEDBACK_OK,
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use std::ffi::CStr;
 use std::fmt;
+use std::io;
 use std::error::Error as StdError;
 use std::result::Result as StdResult;
 use std::str;
 
 use ::libc::{c_int};
 
+use ::claim::UsbClaimant;
+
 /// A specialized `Result` type for working with gphoto2.
 pub type Result<T> = StdResult<T,Error>;
 
@@ -54,6 +57,36 @@ pub enum ErrorKind {
     /// Not enough space when uploading a file.
     NoSpace,
 
+    /// Configuration setting was not found.
+    SettingNotFound,
+
+    /// Storage does not allow writing.
+    ReadOnlyStorage,
+
+    /// Operation timed out.
+    Timeout,
+
+    /// The USB device could not be claimed.
+    ///
+    /// This usually means that another process, such as a desktop environment's volume monitor,
+    /// has already opened the camera.
+    UsbClaim,
+
+    /// Specified port was not found.
+    PortNotFound,
+
+    /// An I/O error occurred while communicating with the camera.
+    IO,
+
+    /// An error occurred in a `libgphoto2` library, e.g., while loading a driver.
+    Library,
+
+    /// Out of memory.
+    NoMemory,
+
+    /// A fixed limit was exceeded, e.g., a file name was too long.
+    FixedLimitExceeded,
+
     /// An unspecified error occured.
     Other,
 }
@@ -62,42 +95,112 @@ pub enum ErrorKind {
 #[derive(Debug)]
 pub struct Error {
     err: c_int,
+    detail: Option<String>,
+    claimants: Vec<UsbClaimant>,
 }
 
 impl Error {
     /// Returns the kind of error.
     pub fn kind(&self) -> ErrorKind {
         match self.err {
-            ::gphoto2::GP_ERROR_BAD_PARAMETERS      => ErrorKind::InvalidInput,
-            ::gphoto2::GP_ERROR_NOT_SUPPORTED       => ErrorKind::NotSupported,
-            ::gphoto2::GP_ERROR_CORRUPTED_DATA      => ErrorKind::CorruptedData,
-            ::gphoto2::GP_ERROR_FILE_EXISTS         => ErrorKind::FileExists,
-            ::gphoto2::GP_ERROR_MODEL_NOT_FOUND     => ErrorKind::ModelNotFound,
-            ::gphoto2::GP_ERROR_DIRECTORY_NOT_FOUND => ErrorKind::DirectoryNotFound,
-            ::gphoto2::GP_ERROR_FILE_NOT_FOUND      => ErrorKind::FileNotFound,
-            ::gphoto2::GP_ERROR_DIRECTORY_EXISTS    => ErrorKind::DirectoryExists,
-            ::gphoto2::GP_ERROR_CAMERA_BUSY         => ErrorKind::CameraBusy,
-            ::gphoto2::GP_ERROR_PATH_NOT_ABSOLUTE   => ErrorKind::PathNotAbsolute,
-            ::gphoto2::GP_ERROR_CANCEL              => ErrorKind::Cancel,
-            ::gphoto2::GP_ERROR_CAMERA_ERROR        => ErrorKind::CameraError,
-            ::gphoto2::GP_ERROR_OS_FAILURE          => ErrorKind::OSFailure,
-            ::gphoto2::GP_ERROR_NO_SPACE            => ErrorKind::NoSpace,
+            ::gphoto2::GP_ERROR_BAD_PARAMETERS       => ErrorKind::InvalidInput,
+            ::gphoto2::GP_ERROR_NOT_SUPPORTED        => ErrorKind::NotSupported,
+            ::gphoto2::GP_ERROR_CORRUPTED_DATA       => ErrorKind::CorruptedData,
+            ::gphoto2::GP_ERROR_FILE_EXISTS          => ErrorKind::FileExists,
+            ::gphoto2::GP_ERROR_MODEL_NOT_FOUND      => ErrorKind::ModelNotFound,
+            ::gphoto2::GP_ERROR_DIRECTORY_NOT_FOUND  => ErrorKind::DirectoryNotFound,
+            ::gphoto2::GP_ERROR_FILE_NOT_FOUND       => ErrorKind::FileNotFound,
+            ::gphoto2::GP_ERROR_DIRECTORY_EXISTS     => ErrorKind::DirectoryExists,
+            ::gphoto2::GP_ERROR_CAMERA_BUSY          => ErrorKind::CameraBusy,
+            ::gphoto2::GP_ERROR_PATH_NOT_ABSOLUTE    => ErrorKind::PathNotAbsolute,
+            ::gphoto2::GP_ERROR_CANCEL               => ErrorKind::Cancel,
+            ::gphoto2::GP_ERROR_CAMERA_ERROR         => ErrorKind::CameraError,
+            ::gphoto2::GP_ERROR_OS_FAILURE           => ErrorKind::OSFailure,
+            ::gphoto2::GP_ERROR_NO_SPACE             => ErrorKind::NoSpace,
+            ::gphoto2::GP_ERROR_TIMEOUT              => ErrorKind::Timeout,
+            ::gphoto2::GP_ERROR_IO_USB_CLAIM         => ErrorKind::UsbClaim,
+            ::gphoto2::GP_ERROR_UNKNOWN_PORT         => ErrorKind::PortNotFound,
+            ::gphoto2::GP_ERROR_LIBRARY              => ErrorKind::Library,
+            ::gphoto2::GP_ERROR_NO_MEMORY            => ErrorKind::NoMemory,
+            ::gphoto2::GP_ERROR_FIXED_LIMIT_EXCEEDED => ErrorKind::FixedLimitExceeded,
+
+            ::gphoto2::GP_ERROR_IO_SUPPORTED_SERIAL  |
+            ::gphoto2::GP_ERROR_IO_SUPPORTED_USB     => ErrorKind::NotSupported,
+
+            ::gphoto2::GP_ERROR_IO                   |
+            ::gphoto2::GP_ERROR_IO_INIT              |
+            ::gphoto2::GP_ERROR_IO_READ              |
+            ::gphoto2::GP_ERROR_IO_WRITE             |
+            ::gphoto2::GP_ERROR_IO_UPDATE            |
+            ::gphoto2::GP_ERROR_IO_SERIAL_SPEED      |
+            ::gphoto2::GP_ERROR_IO_USB_CLEAR_HALT    |
+            ::gphoto2::GP_ERROR_IO_USB_FIND          |
+            ::gphoto2::GP_ERROR_IO_LOCK              |
+            ::gphoto2::GP_ERROR_HAL                  => ErrorKind::IO,
+
+            ERROR_SETTING_NOT_FOUND                  => ErrorKind::SettingNotFound,
+            ERROR_READ_ONLY_STORAGE                  => ErrorKind::ReadOnlyStorage,
 
             ::gphoto2::GP_ERROR | _ => ErrorKind::Other
         }
     }
 
+    /// Returns the raw error code.
+    ///
+    /// Errors reported by `libgphoto2` return one of its `GP_ERROR_*` codes. Errors that are
+    /// detected by this crate, such as `SettingNotFound`, use codes at or below `-10000`, which are
+    /// outside of the range used by `libgphoto2`.
+    pub fn code(&self) -> i32 {
+        self.err
+    }
+
     /// Returns an error message.
     pub fn message(&self) -> &'static str {
+        match self.err {
+            ERROR_SETTING_NOT_FOUND => return "Setting not found",
+            ERROR_READ_ONLY_STORAGE => return "Storage is read-only",
+            _ => ()
+        }
+
         unsafe {
             str::from_utf8_unchecked(CStr::from_ptr(::gphoto2::gp_result_as_string(self.err)).to_bytes())
         }
     }
+
+    /// Returns a detailed description of the error, if one is available.
+    ///
+    /// The detail is the last error message that `libgphoto2` reported through the `Context`
+    /// while performing the failed operation, e.g., `"Could not claim interface 0 (Device or
+    /// resource busy). Make sure no other program (gvfs-gphoto2-volume-monitor) or kernel module
+    /// (such as sdc2xx, stv680, spca50x) is using the device and you have read/write access to
+    /// the device."`. If no error message was reported, the last status message is used instead.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_ref().map(|s| &s[..])
+    }
+
+    /// Returns the processes that hold the camera's USB device open.
+    ///
+    /// This is only reported for errors of kind `UsbClaim` that occur while opening a camera.
+    /// Returns an empty slice if no such processes were found.
+    pub fn claimants(&self) -> &[UsbClaimant] {
+        &self.claimants
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> StdResult<(),fmt::Error> {
-        fmt.write_str(self.message())
+        fmt.write_str(self.message())?;
+
+        if let Some(ref detail) = self.detail {
+            write!(fmt, ": {}", detail)?;
+        }
+
+        if !self.claimants.is_empty() {
+            let claimants = self.claimants.iter().map(|c| format!("{} ({})", c.name(), c.pid())).collect::<Vec<_>>();
+            write!(fmt, " (device in use by {})", claimants.join(", "))?;
+        }
+
+        Ok(())
     }
 }
 
@@ -107,10 +210,62 @@ impl StdError for Error {
     }
 }
 
+impl From<Error> for io::Error {
+    fn from(err: Error) -> io::Error {
+        let kind = match err.kind() {
+            ErrorKind::InvalidInput       => io::ErrorKind::InvalidInput,
+            ErrorKind::PathNotAbsolute    => io::ErrorKind::InvalidInput,
+            ErrorKind::CorruptedData      => io::ErrorKind::InvalidData,
+            ErrorKind::ModelNotFound      => io::ErrorKind::NotFound,
+            ErrorKind::FileNotFound       => io::ErrorKind::NotFound,
+            ErrorKind::DirectoryNotFound  => io::ErrorKind::NotFound,
+            ErrorKind::PortNotFound       => io::ErrorKind::NotFound,
+            ErrorKind::SettingNotFound    => io::ErrorKind::NotFound,
+            ErrorKind::FileExists         => io::ErrorKind::AlreadyExists,
+            ErrorKind::DirectoryExists    => io::ErrorKind::AlreadyExists,
+            ErrorKind::ReadOnlyStorage    => io::ErrorKind::PermissionDenied,
+            ErrorKind::Timeout            => io::ErrorKind::TimedOut,
+            ErrorKind::Cancel             => io::ErrorKind::Interrupted,
+            ErrorKind::IO                 => io::ErrorKind::Other,
+            ErrorKind::UsbClaim           => io::ErrorKind::Other,
+            ErrorKind::CameraBusy         => io::ErrorKind::Other,
+            ErrorKind::NotSupported       => io::ErrorKind::Other,
+            ErrorKind::CameraError        => io::ErrorKind::Other,
+            ErrorKind::OSFailure          => io::ErrorKind::Other,
+            ErrorKind::NoSpace            => io::ErrorKind::Other,
+            ErrorKind::Library            => io::ErrorKind::Other,
+            ErrorKind::NoMemory           => io::ErrorKind::Other,
+            ErrorKind::FixedLimitExceeded => io::ErrorKind::Other,
+            ErrorKind::Other              => io::ErrorKind::Other,
+        };
+
+        io::Error::new(kind, err)
+    }
+}
+
+
+// Error codes for conditions that are detected by this crate rather than `libgphoto2`. These are
+// chosen to be outside of the range used by `libgphoto2`.
+#[doc(hidden)]
+pub const ERROR_SETTING_NOT_FOUND: c_int = -10000;
+
+#[doc(hidden)]
+pub const ERROR_READ_ONLY_STORAGE: c_int = -10001;
 
 #[doc(hidden)]
 pub fn from_libgphoto2(err: c_int) -> Error {
-    Error { err: err }
+    Error { err: err, detail: None, claimants: Vec::new() }
+}
+
+#[doc(hidden)]
+pub fn from_libgphoto2_with_detail(err: c_int, detail: Option<String>) -> Error {
+    Error { err: err, detail: detail, claimants: Vec::new() }
+}
+
+#[doc(hidden)]
+pub fn with_claimants(mut err: Error, claimants: Vec<UsbClaimant>) -> Error {
+    err.claimants = claimants;
+    err
 }
 
 #[doc(hidden)]
@@ -122,3 +277,97 @@ macro_rules! try_unsafe {
         }
     }
 }
+
+/// Like `try_unsafe!`, but attaches the messages that `libgphoto2` reports through `$context` to
+/// the error.
+#[doc(hidden)]
+macro_rules! try_context {
+    ($context:expr, $x:expr) => {{
+        ::context::clear_messages($context);
+
+        match unsafe { $x } {
+            ::gphoto2::GP_OK => (),
+            err => return Err(::context::error($context, err))
+        }
+    }}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::{ErrorKind,from_libgphoto2,from_libgphoto2_with_detail};
+
+    fn kind(err: ::libc::c_int) -> ErrorKind {
+        from_libgphoto2(err).kind()
+    }
+
+    #[test]
+    fn kind_maps_specific_codes() {
+        assert_eq!(ErrorKind::Timeout, kind(::gphoto2::GP_ERROR_TIMEOUT));
+        assert_eq!(ErrorKind::UsbClaim, kind(::gphoto2::GP_ERROR_IO_USB_CLAIM));
+        assert_eq!(ErrorKind::PortNotFound, kind(::gphoto2::GP_ERROR_UNKNOWN_PORT));
+        assert_eq!(ErrorKind::Library, kind(::gphoto2::GP_ERROR_LIBRARY));
+        assert_eq!(ErrorKind::NoMemory, kind(::gphoto2::GP_ERROR_NO_MEMORY));
+        assert_eq!(ErrorKind::FixedLimitExceeded, kind(::gphoto2::GP_ERROR_FIXED_LIMIT_EXCEEDED));
+    }
+
+    #[test]
+    fn kind_groups_io_codes() {
+        assert_eq!(ErrorKind::IO, kind(::gphoto2::GP_ERROR_IO));
+        assert_eq!(ErrorKind::IO, kind(::gphoto2::GP_ERROR_IO_READ));
+        assert_eq!(ErrorKind::IO, kind(::gphoto2::GP_ERROR_IO_USB_FIND));
+        assert_eq!(ErrorKind::IO, kind(::gphoto2::GP_ERROR_HAL));
+        assert_eq!(ErrorKind::NotSupported, kind(::gphoto2::GP_ERROR_IO_SUPPORTED_SERIAL));
+        assert_eq!(ErrorKind::NotSupported, kind(::gphoto2::GP_ERROR_IO_SUPPORTED_USB));
+    }
+
+    #[test]
+    fn kind_maps_crate_codes() {
+        assert_eq!(ErrorKind::SettingNotFound, kind(super::ERROR_SETTING_NOT_FOUND));
+        assert_eq!(ErrorKind::ReadOnlyStorage, kind(super::ERROR_READ_ONLY_STORAGE));
+    }
+
+    #[test]
+    fn kind_defaults_to_other() {
+        assert_eq!(ErrorKind::Other, kind(::gphoto2::GP_ERROR));
+        assert_eq!(ErrorKind::Other, kind(-9999));
+    }
+
+    #[test]
+    fn code_returns_raw_code() {
+        assert_eq!(::gphoto2::GP_ERROR_TIMEOUT, from_libgphoto2(::gphoto2::GP_ERROR_TIMEOUT).code());
+        assert_eq!(-10000, from_libgphoto2(super::ERROR_SETTING_NOT_FOUND).code());
+    }
+
+    #[test]
+    fn detail_returns_reported_message() {
+        let err = from_libgphoto2_with_detail(::gphoto2::GP_ERROR_IO_USB_CLAIM, Some("Could not claim interface 0".to_owned()));
+
+        assert_eq!(Some("Could not claim interface 0"), err.detail());
+        assert_eq!(None, from_libgphoto2(::gphoto2::GP_ERROR_IO_USB_CLAIM).detail());
+    }
+
+    #[test]
+    fn io_error_maps_kind() {
+        let io_kind = |err| io::Error::from(from_libgphoto2(err)).kind();
+
+        assert_eq!(io::ErrorKind::InvalidInput, io_kind(::gphoto2::GP_ERROR_BAD_PARAMETERS));
+        assert_eq!(io::ErrorKind::NotFound, io_kind(::gphoto2::GP_ERROR_FILE_NOT_FOUND));
+        assert_eq!(io::ErrorKind::NotFound, io_kind(super::ERROR_SETTING_NOT_FOUND));
+        assert_eq!(io::ErrorKind::AlreadyExists, io_kind(::gphoto2::GP_ERROR_DIRECTORY_EXISTS));
+        assert_eq!(io::ErrorKind::PermissionDenied, io_kind(super::ERROR_READ_ONLY_STORAGE));
+        assert_eq!(io::ErrorKind::TimedOut, io_kind(::gphoto2::GP_ERROR_TIMEOUT));
+        assert_eq!(io::ErrorKind::Interrupted, io_kind(::gphoto2::GP_ERROR_CANCEL));
+        assert_eq!(io::ErrorKind::Other, io_kind(::gphoto2::GP_ERROR_IO_USB_CLAIM));
+    }
+
+    #[test]
+    fn io_error_wraps_original_error() {
+        let err = io::Error::from(from_libgphoto2_with_detail(::gphoto2::GP_ERROR_TIMEOUT, Some("timed out".to_owned())));
+        let inner = err.get_ref().and_then(|inner| inner.downcast_ref::<super::Error>()).unwrap();
+
+        assert_eq!(::gphoto2::GP_ERROR_TIMEOUT, inner.code());
+        assert_eq!(Some("timed out"), inner.detail());
+    }
+}
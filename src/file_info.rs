@@ -0,0 +1,288 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::ffi::CStr;
+use std::time::SystemTime;
+
+/// Structure containing information about a file on a camera's storage.
+///
+/// The information is divided into three records: one for the file itself, one for its preview
+/// (thumbnail), and one for its audio annotation. Cameras don't necessarily report every field,
+/// so each field is optional.
+///
+/// ## Example
+///
+/// A `FileInfo` object can be used to retrieve information about a file before downloading it:
+///
+/// ```no_run
+/// let mut context = gphoto::Context::new().unwrap();
+/// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+/// let capture = camera.capture_image(&mut context).unwrap();
+/// let info = camera.file_info(&mut context, &capture).unwrap();
+///
+/// println!("         size = {:?}", info.size());
+/// println!("    mime type = {:?}", info.mime_type());
+/// println!("    dimension = {:?} x {:?}", info.width(), info.height());
+/// println!("        mtime = {:?}", info.mtime());
+/// println!("       status = {:?}", info.status());
+/// println!("  permissions = {:?}", info.permissions());
+/// println!(" preview size = {:?}", info.preview().size());
+/// println!(" preview mime = {:?}", info.preview().mime_type());
+/// ```
+///
+/// The above example might print something like the following:
+///
+/// ```text
+///          size = Some(6047744)
+///     mime type = Some("image/jpeg")
+///     dimension = Some(6016) x Some(4016)
+///         mtime = Some(SystemTime { tv_sec: 1445990400, tv_nsec: 0 })
+///        status = Some(NotDownloaded)
+///   permissions = Some({Read, Delete})
+///  preview size = Some(8432)
+///  preview mime = Some("image/jpeg")
+/// ```
+pub struct FileInfo {
+    inner: ::gphoto2::CameraFileInfo,
+}
+
+impl FileInfo {
+    /// The file's size in bytes.
+    pub fn size(&self) -> Option<u64> {
+        if self.inner.file.fields & ::gphoto2::GP_FILE_INFO_SIZE != 0 {
+            Some(self.inner.file.size)
+        }
+        else {
+            None
+        }
+    }
+
+    /// The file's MIME type, e.g., `"image/jpeg"`.
+    pub fn mime_type(&self) -> Option<Cow<str>> {
+        if self.inner.file.fields & ::gphoto2::GP_FILE_INFO_TYPE != 0 {
+            Some(unsafe {
+                String::from_utf8_lossy(CStr::from_ptr(self.inner.file.mime_type.as_ptr()).to_bytes())
+            })
+        }
+        else {
+            None
+        }
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> Option<u32> {
+        if self.inner.file.fields & ::gphoto2::GP_FILE_INFO_WIDTH != 0 {
+            Some(self.inner.file.width)
+        }
+        else {
+            None
+        }
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> Option<u32> {
+        if self.inner.file.fields & ::gphoto2::GP_FILE_INFO_HEIGHT != 0 {
+            Some(self.inner.file.height)
+        }
+        else {
+            None
+        }
+    }
+
+    /// The time that the file was last modified.
+    pub fn mtime(&self) -> Option<SystemTime> {
+        if self.inner.file.fields & ::gphoto2::GP_FILE_INFO_MTIME != 0 {
+            Some(util::system_time(self.inner.file.mtime))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Whether the file has been downloaded.
+    pub fn status(&self) -> Option<FileStatus> {
+        if self.inner.file.fields & ::gphoto2::GP_FILE_INFO_STATUS != 0 {
+            Some(util::file_status(self.inner.file.status))
+        }
+        else {
+            None
+        }
+    }
+
+    /// The operations that are permitted on the file.
+    pub fn permissions(&self) -> Option<HashSet<FilePermission>> {
+        if self.inner.file.fields & ::gphoto2::GP_FILE_INFO_PERMISSIONS != 0 {
+            let mut permissions = HashSet::<FilePermission>::new();
+
+            if self.inner.file.permissions & ::gphoto2::GP_FILE_PERM_READ != 0 {
+                permissions.insert(FilePermission::Read);
+            }
+
+            if self.inner.file.permissions & ::gphoto2::GP_FILE_PERM_DELETE != 0 {
+                permissions.insert(FilePermission::Delete);
+            }
+
+            Some(permissions)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Information about the file's preview.
+    pub fn preview(&self) -> PreviewInfo {
+        PreviewInfo { inner: &self.inner.preview }
+    }
+
+    /// Information about the file's audio data.
+    pub fn audio(&self) -> AudioInfo {
+        AudioInfo { inner: &self.inner.audio }
+    }
+}
+
+/// Structure containing information about the preview of a file.
+pub struct PreviewInfo<'a> {
+    inner: &'a ::gphoto2::CameraFileInfoPreview,
+}
+
+impl<'a> PreviewInfo<'a> {
+    /// The preview's size in bytes.
+    pub fn size(&self) -> Option<u64> {
+        if self.inner.fields & ::gphoto2::GP_FILE_INFO_SIZE != 0 {
+            Some(self.inner.size)
+        }
+        else {
+            None
+        }
+    }
+
+    /// The preview's MIME type, e.g., `"image/jpeg"`.
+    pub fn mime_type(&self) -> Option<Cow<str>> {
+        if self.inner.fields & ::gphoto2::GP_FILE_INFO_TYPE != 0 {
+            Some(unsafe {
+                String::from_utf8_lossy(CStr::from_ptr(self.inner.mime_type.as_ptr()).to_bytes())
+            })
+        }
+        else {
+            None
+        }
+    }
+
+    /// The width of the preview in pixels.
+    pub fn width(&self) -> Option<u32> {
+        if self.inner.fields & ::gphoto2::GP_FILE_INFO_WIDTH != 0 {
+            Some(self.inner.width)
+        }
+        else {
+            None
+        }
+    }
+
+    /// The height of the preview in pixels.
+    pub fn height(&self) -> Option<u32> {
+        if self.inner.fields & ::gphoto2::GP_FILE_INFO_HEIGHT != 0 {
+            Some(self.inner.height)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Whether the preview has been downloaded.
+    pub fn status(&self) -> Option<FileStatus> {
+        if self.inner.fields & ::gphoto2::GP_FILE_INFO_STATUS != 0 {
+            Some(util::file_status(self.inner.status))
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// Structure containing information about the audio data of a file.
+pub struct AudioInfo<'a> {
+    inner: &'a ::gphoto2::CameraFileInfoAudio,
+}
+
+impl<'a> AudioInfo<'a> {
+    /// The audio data's size in bytes.
+    pub fn size(&self) -> Option<u64> {
+        if self.inner.fields & ::gphoto2::GP_FILE_INFO_SIZE != 0 {
+            Some(self.inner.size)
+        }
+        else {
+            None
+        }
+    }
+
+    /// The audio data's MIME type, e.g., `"audio/wav"`.
+    pub fn mime_type(&self) -> Option<Cow<str>> {
+        if self.inner.fields & ::gphoto2::GP_FILE_INFO_TYPE != 0 {
+            Some(unsafe {
+                String::from_utf8_lossy(CStr::from_ptr(self.inner.mime_type.as_ptr()).to_bytes())
+            })
+        }
+        else {
+            None
+        }
+    }
+
+    /// Whether the audio data has been downloaded.
+    pub fn status(&self) -> Option<FileStatus> {
+        if self.inner.fields & ::gphoto2::GP_FILE_INFO_STATUS != 0 {
+            Some(util::file_status(self.inner.status))
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// Download status of a file.
+#[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub enum FileStatus {
+    /// The file has not been downloaded.
+    NotDownloaded,
+
+    /// The file has been downloaded.
+    Downloaded,
+}
+
+/// Operations that are permitted on a file.
+#[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub enum FilePermission {
+    /// The file can be read.
+    Read,
+
+    /// The file can be deleted.
+    Delete,
+}
+
+
+#[doc(hidden)]
+pub fn from_libgphoto2(info: ::gphoto2::CameraFileInfo) -> FileInfo {
+    FileInfo { inner: info }
+}
+
+mod util {
+    use std::time::{Duration,SystemTime,UNIX_EPOCH};
+
+    use super::FileStatus;
+
+    pub fn file_status(status: ::gphoto2::CameraFileStatus) -> FileStatus {
+        match status {
+            ::gphoto2::CameraFileStatus::GP_FILE_STATUS_NOT_DOWNLOADED => FileStatus::NotDownloaded,
+            ::gphoto2::CameraFileStatus::GP_FILE_STATUS_DOWNLOADED     => FileStatus::Downloaded,
+        }
+    }
+
+    pub fn system_time(secs: i64) -> SystemTime {
+        if secs >= 0 {
+            UNIX_EPOCH + Duration::from_secs(secs as u64)
+        }
+        else {
+            UNIX_EPOCH - Duration::from_secs(secs.wrapping_neg() as u64)
+        }
+    }
+}
@@ -1,24 +1,48 @@
 extern crate gphoto2_sys as gphoto2;
 extern crate libc;
 
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+#[cfg(feature = "tokio")]
+extern crate futures_core;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+
 pub use error::{Result,Error,ErrorKind};
-pub use abilities::{Abilities,DeviceType,DriverStatus,CameraOperation,FileOperation,FolderOperation};
-pub use camera::{Camera,CameraFile};
-pub use context::{Context};
-pub use media::{Media,FileMedia};
-pub use port::{PortType,Port};
+#[cfg(feature = "tokio")]
+pub use async_camera::{AsyncCamera,Reply,EventStream};
+pub use abilities::{Abilities,AbilitiesList,AbilitiesIter,DeviceType,DriverStatus,CameraOperation,FileOperation,FolderOperation};
+pub use claim::{ClaimPolicy,UsbClaimant};
+pub use camera::{Camera,CameraFile,CameraEvent,CaptureType,FileType};
+pub use context::{Context,Progress,CancelToken};
+pub use file_info::{FileInfo,PreviewInfo,AudioInfo,FileStatus,FilePermission};
+pub use media::{Media,FileMedia,MemoryMedia,WriterMedia,ReaderMedia};
+pub use port::{PortType,Port,PortInfoList,PortInfoIter,PortInfo};
+pub use shared::{SharedCamera};
 pub use storage::{Storage,StorageType,FilesystemType,AccessType};
 pub use version::{LibraryVersion,libgphoto2_version};
+pub use walk::{Walk};
+pub use widget::{Widget,WidgetType,WidgetValue};
 
 #[macro_use]
 mod error;
 mod abilities;
+#[cfg(feature = "tokio")]
+mod async_camera;
 mod camera;
+mod claim;
 mod context;
+mod file_info;
 mod media;
 mod port;
+mod shared;
 mod storage;
 mod version;
+mod walk;
+mod widget;
 
 // internal
 mod handle;
+mod list;
@@ -0,0 +1,96 @@
+use std::borrow::Cow;
+use std::ptr;
+
+use ::handle::{Handle,HandleMut};
+
+/// A list of name/value pairs returned by `libgphoto2`.
+pub struct CameraList {
+    list: *mut ::gphoto2::CameraList,
+}
+
+impl Drop for CameraList {
+    fn drop(&mut self) {
+        unsafe {
+            ::gphoto2::gp_list_unref(self.list);
+        }
+    }
+}
+
+impl CameraList {
+    /// Creates a new empty list.
+    pub fn new() -> ::Result<Self> {
+        let mut ptr = ptr::null_mut();
+
+        try_unsafe!(::gphoto2::gp_list_new(&mut ptr));
+
+        Ok(CameraList { list: ptr })
+    }
+
+    /// Returns the number of entries in the list.
+    pub fn len(&self) -> usize {
+        let count = unsafe { ::gphoto2::gp_list_count(self.list) };
+
+        if count < 0 { 0 } else { count as usize }
+    }
+
+    /// Returns the name of the entry at `index`.
+    pub fn name(&self, index: usize) -> Cow<str> {
+        String::from_utf8_lossy(self.name_bytes(index))
+    }
+
+    /// Returns the name of the entry at `index` without decoding it.
+    ///
+    /// File names on the camera's storage aren't necessarily valid UTF-8, so this should be used
+    /// instead of `name()` when the name is passed back to `libgphoto2`.
+    pub fn name_bytes(&self, index: usize) -> &[u8] {
+        let mut name = ptr::null();
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_list_get_name(self.list, index as i32, &mut name));
+            util::bytes(name)
+        }
+    }
+
+    /// Returns the value of the entry at `index`.
+    pub fn value(&self, index: usize) -> Cow<str> {
+        let mut value = ptr::null();
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_list_get_value(self.list, index as i32, &mut value));
+            util::lossy_str(value)
+        }
+    }
+}
+
+impl Handle<::gphoto2::CameraList> for CameraList {
+    unsafe fn as_ptr(&self) -> *const ::gphoto2::CameraList {
+        self.list
+    }
+}
+
+impl HandleMut<::gphoto2::CameraList> for CameraList {
+    unsafe fn as_mut_ptr(&mut self) -> *mut ::gphoto2::CameraList {
+        self.list
+    }
+}
+
+// Also used by the context's callbacks, which receive messages as C strings.
+pub mod util {
+    use std::borrow::Cow;
+    use std::ffi::CStr;
+
+    use ::libc::c_char;
+
+    pub unsafe fn bytes<'a>(ptr: *const c_char) -> &'a [u8] {
+        if ptr.is_null() {
+            &[]
+        }
+        else {
+            CStr::from_ptr(ptr).to_bytes()
+        }
+    }
+
+    pub unsafe fn lossy_str<'a>(ptr: *const c_char) -> Cow<'a, str> {
+        String::from_utf8_lossy(bytes(ptr))
+    }
+}
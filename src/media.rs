@@ -1,9 +1,14 @@
-use std::ffi::{CString};
-use std::mem;
+use std::borrow::Cow;
+use std::ffi::CString;
+use std::io::{Read,Write};
 use std::path::Path;
+use std::ptr;
+use std::slice;
 
 use std::os::unix::prelude::*;
 
+use ::libc::c_void;
+
 
 /// A trait for types that can store media.
 pub trait Media {
@@ -48,7 +53,45 @@ impl FileMedia {
             return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_FILE_EXISTS));
         }
 
-        let mut ptr = unsafe { mem::uninitialized() };
+        let mut ptr = ptr::null_mut();
+
+        match unsafe { ::gphoto2::gp_file_new_from_fd(&mut ptr, fd) } {
+            ::gphoto2::GP_OK => {
+                Ok(FileMedia { file: ptr })
+            },
+            err => {
+                unsafe {
+                    ::libc::close(fd);
+                }
+
+                Err(::error::from_libgphoto2(err))
+            }
+        }
+    }
+
+    /// Opens an existing file as media.
+    ///
+    /// This function opens a file on disk for reading, e.g., to upload it to a camera.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the file can not be opened:
+    ///
+    /// * `FileNotFound` if the file doesn't exist or can't be read.
+    pub fn open(path: &Path) -> ::Result<Self> {
+        use ::libc::{O_RDONLY};
+
+        let cstr = match CString::new(path.as_os_str().as_bytes()) {
+            Ok(s) => s,
+            Err(_) => return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_BAD_PARAMETERS))
+        };
+
+        let fd = unsafe { ::libc::open(cstr.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_FILE_NOT_FOUND));
+        }
+
+        let mut ptr = ptr::null_mut();
 
         match unsafe { ::gphoto2::gp_file_new_from_fd(&mut ptr, fd) } {
             ::gphoto2::GP_OK => {
@@ -71,3 +114,440 @@ impl Media for FileMedia {
         self.file
     }
 }
+
+
+/// Media stored in memory.
+///
+/// ## Example
+///
+/// A `MemoryMedia` object can be used to download a file without writing it to disk:
+///
+/// ```no_run
+/// let mut context = gphoto::Context::new().unwrap();
+/// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+/// let capture = camera.capture_image(&mut context).unwrap();
+///
+/// let mut media = gphoto::MemoryMedia::new().unwrap();
+/// camera.download(&mut context, &capture, &mut media).unwrap();
+///
+/// println!("     name = {:?}", media.name());
+/// println!("mime type = {:?}", media.mime_type());
+/// println!("     size = {:?}", media.data().len());
+/// ```
+pub struct MemoryMedia {
+    file: *mut ::gphoto2::CameraFile,
+}
+
+impl Drop for MemoryMedia {
+    fn drop(&mut self) {
+        unsafe {
+            ::gphoto2::gp_file_unref(self.file);
+        }
+    }
+}
+
+impl MemoryMedia {
+    /// Creates a new in-memory media buffer.
+    ///
+    /// The buffer will start out empty.
+    pub fn new() -> ::Result<Self> {
+        let mut ptr = ptr::null_mut();
+
+        try_unsafe!(::gphoto2::gp_file_new(&mut ptr));
+
+        Ok(MemoryMedia { file: ptr })
+    }
+
+    /// Returns the media's contents.
+    pub fn data(&self) -> &[u8] {
+        let mut data = ptr::null();
+        let mut size = 0;
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_file_get_data_and_size(self.file, &mut data, &mut size));
+        }
+
+        if data.is_null() {
+            &[]
+        }
+        else {
+            unsafe { slice::from_raw_parts(data as *const u8, size as usize) }
+        }
+    }
+
+    /// Returns a copy of the media's contents.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.data().to_vec()
+    }
+
+    /// Returns the name of the file that the media was downloaded from.
+    ///
+    /// Returns `None` if the name is unknown.
+    pub fn name(&self) -> Option<Cow<str>> {
+        let mut name = ptr::null();
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_file_get_name(self.file, &mut name));
+            util::non_empty_str(name)
+        }
+    }
+
+    /// Returns the MIME type of the media, e.g., `"image/jpeg"`.
+    ///
+    /// Returns `None` if the MIME type is unknown.
+    pub fn mime_type(&self) -> Option<Cow<str>> {
+        let mut mime_type = ptr::null();
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_file_get_mime_type(self.file, &mut mime_type));
+            util::non_empty_str(mime_type)
+        }
+    }
+}
+
+impl Media for MemoryMedia {
+    #[doc(hidden)]
+    unsafe fn as_mut_ptr(&mut self) -> *mut ::gphoto2::CameraFile {
+        self.file
+    }
+}
+
+
+/// Media that streams its contents to a writer.
+///
+/// Data downloaded into a `WriterMedia` is passed to the writer as it's received from the camera,
+/// without buffering the whole file in memory. The writer is not flushed automatically; use
+/// `get_mut()` or `into_inner()` to flush it after downloading.
+///
+/// ## Example
+///
+/// A `WriterMedia` object can be used to stream a file to any `Write` implementation:
+///
+/// ```no_run
+/// use std::io::{self,Write};
+///
+/// let mut context = gphoto::Context::new().unwrap();
+/// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+/// let capture = camera.capture_image(&mut context).unwrap();
+///
+/// let stdout = io::stdout();
+/// let mut media = gphoto::WriterMedia::new(stdout.lock()).unwrap();
+/// camera.download(&mut context, &capture, &mut media).unwrap();
+///
+/// media.into_inner().flush().unwrap();
+/// ```
+pub struct WriterMedia<W: Write> {
+    file: *mut ::gphoto2::CameraFile,
+    state: *mut handler::Writer<W>,
+}
+
+impl<W: Write> Drop for WriterMedia<W> {
+    fn drop(&mut self) {
+        if !self.file.is_null() {
+            unsafe {
+                ::gphoto2::gp_file_unref(self.file);
+            }
+        }
+
+        if !self.state.is_null() {
+            unsafe {
+                drop(Box::from_raw(self.state));
+            }
+        }
+    }
+}
+
+impl<W: Write> WriterMedia<W> {
+    /// Creates media that writes its contents to `writer`.
+    pub fn new(writer: W) -> ::Result<Self> {
+        let state = Box::into_raw(Box::new(handler::Writer {
+            handler: handler::Handler {
+                size: handler::writer_size::<W>,
+                read: handler::writer_read,
+                write: handler::writer_write::<W>,
+            },
+            writer: writer,
+            written: 0,
+        }));
+
+        let mut ptr = ptr::null_mut();
+
+        match unsafe { ::gphoto2::gp_file_new_from_handler(&mut ptr, &mut (*state).handler as *mut handler::Handler as *mut ::gphoto2::CameraFileHandler, state as *mut c_void) } {
+            ::gphoto2::GP_OK => Ok(WriterMedia { file: ptr, state: state }),
+            err => {
+                unsafe {
+                    drop(Box::from_raw(state));
+                }
+
+                Err(::error::from_libgphoto2(err))
+            }
+        }
+    }
+
+    /// Returns a reference to the writer.
+    pub fn get_ref(&self) -> &W {
+        unsafe { &(*self.state).writer }
+    }
+
+    /// Returns a mutable reference to the writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        unsafe { &mut (*self.state).writer }
+    }
+
+    /// Returns the number of bytes that have been written to the writer.
+    pub fn bytes_written(&self) -> u64 {
+        unsafe { (*self.state).written }
+    }
+
+    /// Unwraps the media, returning the writer.
+    pub fn into_inner(mut self) -> W {
+        let state = unsafe {
+            ::gphoto2::gp_file_unref(self.file);
+            Box::from_raw(self.state)
+        };
+
+        self.file = ptr::null_mut();
+        self.state = ptr::null_mut();
+
+        state.writer
+    }
+}
+
+impl<W: Write> Media for WriterMedia<W> {
+    #[doc(hidden)]
+    unsafe fn as_mut_ptr(&mut self) -> *mut ::gphoto2::CameraFile {
+        self.file
+    }
+}
+
+
+/// Media that reads its contents from a reader.
+///
+/// A `ReaderMedia` can be used to upload data from any `Read` implementation. The size of the
+/// data must be known in advance, because `libgphoto2` asks for it before reading. Note that
+/// `libgphoto2` reads the complete contents into memory before transferring them to the camera.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::io::Cursor;
+///
+/// let mut context = gphoto::Context::new().unwrap();
+/// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+///
+/// let data = b"hello, camera".to_vec();
+/// let size = data.len() as u64;
+/// let mut media = gphoto::ReaderMedia::new(Cursor::new(data), size).unwrap();
+///
+/// camera.upload(&mut context, "/store_00010001", "HELLO.TXT", &mut media, gphoto::FileType::Normal).unwrap();
+/// ```
+pub struct ReaderMedia<R: Read> {
+    file: *mut ::gphoto2::CameraFile,
+    state: *mut handler::Reader<R>,
+}
+
+impl<R: Read> Drop for ReaderMedia<R> {
+    fn drop(&mut self) {
+        if !self.file.is_null() {
+            unsafe {
+                ::gphoto2::gp_file_unref(self.file);
+            }
+        }
+
+        if !self.state.is_null() {
+            unsafe {
+                drop(Box::from_raw(self.state));
+            }
+        }
+    }
+}
+
+impl<R: Read> ReaderMedia<R> {
+    /// Creates media that reads `size` bytes from `reader`.
+    ///
+    /// If the reader ends before `size` bytes have been read, only the bytes that were read are
+    /// transferred.
+    pub fn new(reader: R, size: u64) -> ::Result<Self> {
+        let state = Box::into_raw(Box::new(handler::Reader {
+            handler: handler::Handler {
+                size: handler::reader_size::<R>,
+                read: handler::reader_read::<R>,
+                write: handler::reader_write,
+            },
+            reader: reader,
+            size: size,
+        }));
+
+        let mut ptr = ptr::null_mut();
+
+        match unsafe { ::gphoto2::gp_file_new_from_handler(&mut ptr, &mut (*state).handler as *mut handler::Handler as *mut ::gphoto2::CameraFileHandler, state as *mut c_void) } {
+            ::gphoto2::GP_OK => Ok(ReaderMedia { file: ptr, state: state }),
+            err => {
+                unsafe {
+                    drop(Box::from_raw(state));
+                }
+
+                Err(::error::from_libgphoto2(err))
+            }
+        }
+    }
+
+    /// Returns a reference to the reader.
+    pub fn get_ref(&self) -> &R {
+        unsafe { &(*self.state).reader }
+    }
+
+    /// Returns a mutable reference to the reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        unsafe { &mut (*self.state).reader }
+    }
+
+    /// Unwraps the media, returning the reader.
+    pub fn into_inner(mut self) -> R {
+        let state = unsafe {
+            ::gphoto2::gp_file_unref(self.file);
+            Box::from_raw(self.state)
+        };
+
+        self.file = ptr::null_mut();
+        self.state = ptr::null_mut();
+
+        state.reader
+    }
+}
+
+impl<R: Read> Media for ReaderMedia<R> {
+    #[doc(hidden)]
+    unsafe fn as_mut_ptr(&mut self) -> *mut ::gphoto2::CameraFile {
+        self.file
+    }
+}
+
+
+// `libgphoto2` calls the handler's functions with the pointer that was given to
+// `gp_file_new_from_handler()`, which points to the `Writer` or `Reader` that the media allocated
+// with `Box::into_raw()`. The media only accesses its state through the same pointer and frees it
+// after the `CameraFile`, so the state stays at a stable address for as long as it's in use.
+mod handler {
+    use std::io::{ErrorKind,Read,Write};
+    use std::slice;
+
+    use ::libc::{c_int,c_uchar,c_void};
+
+    // Same layout as `CameraFileHandler`, whose fields aren't public in `gphoto2-sys`. The handler
+    // has no seek function, so writers don't need to implement `Seek`.
+    #[repr(C)]
+    pub struct Handler {
+        pub size: extern "C" fn(prv: *mut c_void, size: *mut u64) -> c_int,
+        pub read: extern "C" fn(prv: *mut c_void, data: *mut c_uchar, len: *mut u64) -> c_int,
+        pub write: extern "C" fn(prv: *mut c_void, data: *mut c_uchar, len: *mut u64) -> c_int,
+    }
+
+    pub struct Writer<W> {
+        pub handler: Handler,
+        pub writer: W,
+        pub written: u64,
+    }
+
+    pub struct Reader<R> {
+        pub handler: Handler,
+        pub reader: R,
+        pub size: u64,
+    }
+
+    pub extern "C" fn writer_size<W: Write>(prv: *mut c_void, size: *mut u64) -> c_int {
+        let state = unsafe { &*(prv as *const Writer<W>) };
+
+        unsafe {
+            *size = state.written;
+        }
+
+        ::gphoto2::GP_OK
+    }
+
+    pub extern "C" fn writer_read(_prv: *mut c_void, _data: *mut c_uchar, _len: *mut u64) -> c_int {
+        ::gphoto2::GP_ERROR_NOT_SUPPORTED
+    }
+
+    pub extern "C" fn writer_write<W: Write>(prv: *mut c_void, data: *mut c_uchar, len: *mut u64) -> c_int {
+        let state = unsafe { &mut *(prv as *mut Writer<W>) };
+        let len = unsafe { *len };
+
+        if len == 0 {
+            return ::gphoto2::GP_OK;
+        }
+
+        let buf = unsafe { slice::from_raw_parts(data as *const u8, len as usize) };
+
+        // `libgphoto2` assumes that everything is written, so partial writes aren't reported.
+        match state.writer.write_all(buf) {
+            Ok(()) => {
+                state.written += len;
+                ::gphoto2::GP_OK
+            },
+            Err(_) => ::gphoto2::GP_ERROR_IO_WRITE
+        }
+    }
+
+    pub extern "C" fn reader_size<R: Read>(prv: *mut c_void, size: *mut u64) -> c_int {
+        let state = unsafe { &*(prv as *const Reader<R>) };
+
+        unsafe {
+            *size = state.size;
+        }
+
+        ::gphoto2::GP_OK
+    }
+
+    pub extern "C" fn reader_read<R: Read>(prv: *mut c_void, data: *mut c_uchar, len: *mut u64) -> c_int {
+        let state = unsafe { &mut *(prv as *mut Reader<R>) };
+
+        if unsafe { *len } == 0 {
+            return ::gphoto2::GP_OK;
+        }
+
+        let buf = unsafe { slice::from_raw_parts_mut(data, *len as usize) };
+        let mut total = 0;
+
+        while total < buf.len() {
+            match state.reader.read(&mut buf[total..]) {
+                Ok(0) => break,
+                Ok(n) => total += n,
+                Err(ref err) if err.kind() == ErrorKind::Interrupted => (),
+                Err(_) => return ::gphoto2::GP_ERROR_IO_READ
+            }
+        }
+
+        unsafe {
+            *len = total as u64;
+        }
+
+        ::gphoto2::GP_OK
+    }
+
+    pub extern "C" fn reader_write(_prv: *mut c_void, _data: *mut c_uchar, _len: *mut u64) -> c_int {
+        ::gphoto2::GP_ERROR_NOT_SUPPORTED
+    }
+}
+
+mod util {
+    use std::borrow::Cow;
+    use std::ffi::CStr;
+
+    use ::libc::c_char;
+
+    pub unsafe fn non_empty_str<'a>(ptr: *const c_char) -> Option<Cow<'a, str>> {
+        if ptr.is_null() {
+            return None;
+        }
+
+        let bytes = CStr::from_ptr(ptr).to_bytes();
+
+        if bytes.is_empty() {
+            None
+        }
+        else {
+            Some(String::from_utf8_lossy(bytes))
+        }
+    }
+}
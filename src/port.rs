@@ -1,12 +1,13 @@
 use std::borrow::Cow;
-use std::ffi::CStr;
+use std::ffi::CString;
 use std::marker::PhantomData;
-use std::mem;
+use std::ptr;
 
 use ::libc::c_void;
 
 /// Types of ports.
 #[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
 pub enum PortType {
     /// Serial port.
     Serial,
@@ -63,41 +64,204 @@ pub struct Port<'a> {
 impl<'a> Port<'a> {
     /// Returns the type of the port.
     pub fn port_type(&self) -> PortType {
-        let mut port_type = unsafe { mem::uninitialized() };
+        util::port_type(self.inner)
+    }
+
+    /// Returns the name of the port.
+    pub fn name(&self) -> Cow<str> {
+        unsafe { util::name(self.inner) }
+    }
 
+    /// Returns the path of the port.
+    pub fn path(&self) -> Cow<str> {
+        unsafe { util::path(self.inner) }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> ::serde::Serialize for Port<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok,S::Error>
+        where S: ::serde::Serializer
+    {
+        use ::serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Port", 3)?;
+        state.serialize_field("port_type", &self.port_type())?;
+        state.serialize_field("name", &self.name())?;
+        state.serialize_field("path", &self.path())?;
+        state.end()
+    }
+}
+
+/// A list of the ports available on the system.
+///
+/// ## Example
+///
+/// A `PortInfoList` can be used to list ports without opening a camera:
+///
+/// ```no_run
+/// let ports = gphoto::PortInfoList::load().unwrap();
+///
+/// for port in ports.iter() {
+///     println!("{:?} {:?} {:?}", port.port_type(), port.name(), port.path());
+/// }
+/// ```
+///
+/// The above example may print something like the following:
+///
+/// ```text
+/// USB "Universal Serial Bus" "usb:020,007"
+/// PTPIP "PTP/IP Connection" "ptpip:"
+/// Disk "Media '/'" "disk:/"
+/// ```
+pub struct PortInfoList {
+    list: *mut ::gphoto2::GPPortInfoList,
+}
+
+impl Drop for PortInfoList {
+    fn drop(&mut self) {
         unsafe {
-            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_port_info_get_type(self.inner, &mut port_type));
+            ::gphoto2::gp_port_info_list_free(self.list);
         }
+    }
+}
 
-        match port_type {
-            ::gphoto2::GP_PORT_SERIAL          => PortType::Serial,
-            ::gphoto2::GP_PORT_USB             => PortType::USB,
-            ::gphoto2::GP_PORT_DISK            => PortType::Disk,
-            ::gphoto2::GP_PORT_PTPIP           => PortType::PTPIP,
-            ::gphoto2::GP_PORT_USB_DISK_DIRECT => PortType::Direct,
-            ::gphoto2::GP_PORT_USB_SCSI        => PortType::SCSI,
-            ::gphoto2::GP_PORT_NONE | _        => PortType::Other,
+impl PortInfoList {
+    /// Loads the list of available ports.
+    pub fn load() -> ::Result<Self> {
+        let mut ptr = ptr::null();
+
+        try_unsafe!(::gphoto2::gp_port_info_list_new(&mut ptr));
+
+        let list = PortInfoList { list: ptr as *mut ::gphoto2::GPPortInfoList };
+
+        match unsafe { ::gphoto2::gp_port_info_list_load(list.list) } {
+            err if err < 0 => Err(::error::from_libgphoto2(err)),
+            _ => Ok(list)
         }
     }
 
-    /// Returns the name of the port.
-    pub fn name(&self) -> Cow<str> {
-        let mut name = unsafe { mem::uninitialized() };
+    /// Returns the number of ports in the list.
+    pub fn len(&self) -> usize {
+        let count = unsafe { ::gphoto2::gp_port_info_list_count(self.list) };
 
-        unsafe {
-            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_port_info_get_name(self.inner, &mut name));
-            String::from_utf8_lossy(CStr::from_ptr(name).to_bytes())
+        if count < 0 { 0 } else { count as usize }
+    }
+
+    /// Returns `true` if the list contains no ports.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns information about the port at `index`.
+    ///
+    /// Returns `None` if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> Option<PortInfo> {
+        self.raw_info(index).map(|info| {
+            unsafe {
+                PortInfo {
+                    port_type: util::port_type(info),
+                    name: util::name(info).into_owned(),
+                    path: util::path(info).into_owned(),
+                }
+            }
+        })
+    }
+
+    /// Returns an iterator over all ports in the list.
+    pub fn iter(&self) -> PortInfoIter {
+        PortInfoIter {
+            list: self,
+            index: 0,
         }
     }
 
-    /// Returns the path of the port.
-    pub fn path(&self) -> Cow<str> {
-        let mut path = unsafe { mem::uninitialized() };
+    /// Finds a port by its path, e.g., `"usb:020,007"`.
+    pub fn find_path(&self, path: &str) -> Option<PortInfo> {
+        self.lookup_path(path).and_then(|index| self.get(index))
+    }
+
+    fn lookup_path(&self, path: &str) -> Option<usize> {
+        let cstr = match CString::new(path) {
+            Ok(s) => s,
+            Err(_) => return None
+        };
+
+        match unsafe { ::gphoto2::gp_port_info_list_lookup_path(self.list, cstr.as_ptr()) } {
+            index if index >= 0 => Some(index as usize),
+            _ => None
+        }
+    }
+
+    fn raw_info(&self, index: usize) -> Option<::gphoto2::GPPortInfo> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let mut info = ptr::null_mut();
 
         unsafe {
-            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_port_info_get_path(self.inner, &mut path));
-            String::from_utf8_lossy(CStr::from_ptr(path).to_bytes())
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_port_info_list_get_info(self.list, index as i32, &mut info));
         }
+
+        Some(info)
+    }
+}
+
+impl<'a> IntoIterator for &'a PortInfoList {
+    type Item = PortInfo;
+    type IntoIter = PortInfoIter<'a>;
+
+    fn into_iter(self) -> PortInfoIter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the ports in a `PortInfoList`.
+pub struct PortInfoIter<'a> {
+    list: &'a PortInfoList,
+    index: usize,
+}
+
+impl<'a> Iterator for PortInfoIter<'a> {
+    type Item = PortInfo;
+
+    fn next(&mut self) -> Option<PortInfo> {
+        let info = self.list.get(self.index);
+
+        if info.is_some() {
+            self.index += 1;
+        }
+
+        info
+    }
+}
+
+/// Owned information about a port.
+///
+/// Unlike `Port`, a `PortInfo` is not tied to the lifetime of a camera.
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct PortInfo {
+    port_type: PortType,
+    name: String,
+    path: String,
+}
+
+impl PortInfo {
+    /// Returns the type of the port.
+    pub fn port_type(&self) -> PortType {
+        self.port_type
+    }
+
+    /// Returns the name of the port.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path of the port.
+    pub fn path(&self) -> &str {
+        &self.path
     }
 }
 
@@ -108,3 +272,48 @@ pub fn from_libgphoto2<'a>(_camera: &'a ::camera::Camera, ptr: ::gphoto2::GPPort
         __phantom: PhantomData,
     }
 }
+
+#[doc(hidden)]
+pub fn info_by_path(list: &PortInfoList, path: &str) -> Option<::gphoto2::GPPortInfo> {
+    list.lookup_path(path).and_then(|index| list.raw_info(index))
+}
+
+mod util {
+    use std::borrow::Cow;
+    use std::ffi::CStr;
+    use std::ptr;
+
+    use super::PortType;
+
+    pub fn port_type(info: ::gphoto2::GPPortInfo) -> PortType {
+        let mut port_type = 0;
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_port_info_get_type(info, &mut port_type));
+        }
+
+        match port_type {
+            ::gphoto2::GP_PORT_SERIAL          => PortType::Serial,
+            ::gphoto2::GP_PORT_USB             => PortType::USB,
+            ::gphoto2::GP_PORT_DISK            => PortType::Disk,
+            ::gphoto2::GP_PORT_PTPIP           => PortType::PTPIP,
+            ::gphoto2::GP_PORT_USB_DISK_DIRECT => PortType::Direct,
+            ::gphoto2::GP_PORT_USB_SCSI        => PortType::SCSI,
+            ::gphoto2::GP_PORT_NONE | _        => PortType::Other,
+        }
+    }
+
+    pub unsafe fn name<'a>(info: ::gphoto2::GPPortInfo) -> Cow<'a, str> {
+        let mut name = ptr::null();
+
+        assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_port_info_get_name(info, &mut name));
+        String::from_utf8_lossy(CStr::from_ptr(name).to_bytes())
+    }
+
+    pub unsafe fn path<'a>(info: ::gphoto2::GPPortInfo) -> Cow<'a, str> {
+        let mut path = ptr::null();
+
+        assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_port_info_get_path(info, &mut path));
+        String::from_utf8_lossy(CStr::from_ptr(path).to_bytes())
+    }
+}
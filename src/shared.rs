@@ -0,0 +1,72 @@
+use std::sync::{Arc,Mutex,MutexGuard};
+
+use ::camera::Camera;
+
+/// A camera that can be shared between threads.
+///
+/// A `SharedCamera` is a cloneable handle to a camera that's guarded by a mutex. Each operation
+/// locks the camera for its duration, so operations from different threads are performed one at a
+/// time. Each thread still needs its own `Context`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::thread;
+///
+/// let mut context = gphoto::Context::new().unwrap();
+/// let camera = gphoto::SharedCamera::new(gphoto::Camera::autodetect(&mut context).unwrap());
+///
+/// let workers = (0..4).map(|_| {
+///     let camera = camera.clone();
+///
+///     thread::spawn(move || {
+///         let mut context = gphoto::Context::new().unwrap();
+///         let capture = camera.lock().capture_image(&mut context).unwrap();
+///
+///         println!("captured {}", capture.basename());
+///     })
+/// }).collect::<Vec<_>>();
+///
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SharedCamera {
+    camera: Arc<Mutex<Camera>>,
+}
+
+impl SharedCamera {
+    /// Creates a shared handle to `camera`.
+    pub fn new(camera: Camera) -> Self {
+        SharedCamera { camera: Arc::new(Mutex::new(camera)) }
+    }
+
+    /// Locks the camera, blocking until no other thread is operating it.
+    ///
+    /// The camera is unlocked when the returned guard is dropped. Several operations can be
+    /// performed while holding the guard without another thread's operations interleaving with
+    /// them, e.g., changing a setting and capturing an image with it.
+    ///
+    /// If a thread panicked while holding the lock, the camera is still returned.
+    pub fn lock(&self) -> MutexGuard<'_, Camera> {
+        self.camera.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Runs `f` with the camera locked and returns its result.
+    pub fn with<F,T>(&self, f: F) -> T
+        where F: FnOnce(&mut Camera) -> T
+    {
+        f(&mut self.lock())
+    }
+
+    /// Returns the camera if this is its only handle.
+    ///
+    /// Otherwise, returns the handle again as the error.
+    pub fn try_unwrap(self) -> Result<Camera,SharedCamera> {
+        match Arc::try_unwrap(self.camera) {
+            Ok(mutex) => Ok(mutex.into_inner().unwrap_or_else(|err| err.into_inner())),
+            Err(camera) => Err(SharedCamera { camera: camera }),
+        }
+    }
+}
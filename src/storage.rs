@@ -1,5 +1,4 @@
 use std::borrow::Cow;
-use std::ffi::CStr;
 
 /// Structure containing information about a camera's storage.
 ///
@@ -37,128 +36,72 @@ use std::ffi::CStr;
 ///         free kb = Some(30833088)
 ///     free images = Some(580)
 /// ```
-#[repr(C)]
+#[derive(Debug,Clone,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
 pub struct Storage {
-    inner: ::gphoto2::CameraStorageInformation,
+    base_dir: Option<String>,
+    label: Option<String>,
+    description: Option<String>,
+    storage_type: Option<StorageType>,
+    filesystem_type: Option<FilesystemType>,
+    access_type: Option<AccessType>,
+    capacity_kbytes: Option<u64>,
+    free_kbytes: Option<u64>,
+    free_images: Option<u64>,
 }
 
 impl Storage {
     /// Base directory of the storage.
     pub fn base_dir(&self) -> Option<Cow<str>> {
-        if self.inner.fields & ::gphoto2::GP_STORAGEINFO_BASE != 0 {
-            Some(unsafe {
-                String::from_utf8_lossy(CStr::from_ptr(self.inner.basedir.as_ptr()).to_bytes())
-            })
-        }
-        else {
-            None
-        }
+        self.base_dir.as_ref().map(|s| Cow::Borrowed(&s[..]))
     }
 
     /// The storage's label.
     pub fn label(&self) -> Option<Cow<str>> {
-        if self.inner.fields & ::gphoto2::GP_STORAGEINFO_LABEL != 0 {
-            Some(unsafe {
-                String::from_utf8_lossy(CStr::from_ptr(self.inner.label.as_ptr()).to_bytes())
-            })
-        }
-        else {
-            None
-        }
+        self.label.as_ref().map(|s| Cow::Borrowed(&s[..]))
     }
 
     /// A description of the storage.
     pub fn description(&self) -> Option<Cow<str>> {
-        if self.inner.fields & ::gphoto2::GP_STORAGEINFO_DESCRIPTION != 0 {
-            Some(unsafe {
-                String::from_utf8_lossy(CStr::from_ptr(self.inner.description.as_ptr()).to_bytes())
-            })
-        }
-        else {
-            None
-        }
+        self.description.as_ref().map(|s| Cow::Borrowed(&s[..]))
     }
 
     /// The storage's hardware type.
     pub fn storage_type(&self) -> Option<StorageType> {
-        if self.inner.fields & ::gphoto2::GP_STORAGEINFO_STORAGETYPE != 0 {
-            Some(match self.inner.storage_type {
-                ::gphoto2::GP_STORAGEINFO_ST_FIXED_ROM     => StorageType::FixedRom,
-                ::gphoto2::GP_STORAGEINFO_ST_REMOVABLE_ROM => StorageType::RemovableRom,
-                ::gphoto2::GP_STORAGEINFO_ST_FIXED_RAM     => StorageType::FixedRam,
-                ::gphoto2::GP_STORAGEINFO_ST_REMOVABLE_RAM => StorageType::RemoveableRam,
-                ::gphoto2::GP_STORAGEINFO_ST_UNKNOWN       => StorageType::Unknown,
-            })
-        }
-        else {
-            None
-        }
+        self.storage_type
     }
 
     /// The hiearchy type of the storage's filesystem.
     pub fn filesystem_type(&self) -> Option<FilesystemType> {
-        if self.inner.fields & ::gphoto2::GP_STORAGEINFO_FILESYSTEMTYPE != 0 {
-            Some(match self.inner.fstype {
-                ::gphoto2::GP_STORAGEINFO_FST_GENERICFLAT         => FilesystemType::Flat,
-                ::gphoto2::GP_STORAGEINFO_FST_GENERICHIERARCHICAL => FilesystemType::Hierarchical,
-                ::gphoto2::GP_STORAGEINFO_FST_DCF                 => FilesystemType::DCF,
-                ::gphoto2::GP_STORAGEINFO_FST_UNDEFINED           => FilesystemType::Unknown,
-            })
-        }
-        else {
-            None
-        }
+        self.filesystem_type
     }
 
     /// The storage's access permissions.
     pub fn access_type(&self) -> Option<AccessType> {
-        if self.inner.fields & ::gphoto2::GP_STORAGEINFO_ACCESS != 0 {
-            Some(match self.inner.access {
-                ::gphoto2::GP_STORAGEINFO_AC_READWRITE            => AccessType::ReadWrite,
-                ::gphoto2::GP_STORAGEINFO_AC_READONLY             => AccessType::ReadOnly,
-                ::gphoto2::GP_STORAGEINFO_AC_READONLY_WITH_DELETE => AccessType::ReadDelete,
-            })
-        }
-        else {
-            None
-        }
+        self.access_type
     }
 
     /// The storage's total capacity in kilobytes.
     pub fn capacity_kbytes(&self) -> Option<u64> {
-        if self.inner.fields & ::gphoto2::GP_STORAGEINFO_MAXCAPACITY != 0 {
-            Some(self.inner.capacitykbytes)
-        }
-        else {
-            None
-        }
+        self.capacity_kbytes
     }
 
     /// The storage's free space in kilobytes.
     pub fn free_kbytes(&self) -> Option<u64> {
-        if self.inner.fields & ::gphoto2::GP_STORAGEINFO_FREESPACEKBYTES != 0 {
-            Some(self.inner.freekbytes)
-        }
-        else {
-            None
-        }
+        self.free_kbytes
     }
 
     /// An estimate of the number of images that could fit in the storage's remaining space.
     ///
     /// This value is estimated by the camera.
     pub fn free_images(&self) -> Option<u64> {
-        if self.inner.fields & ::gphoto2::GP_STORAGEINFO_FREESPACEIMAGES != 0 {
-            Some(self.inner.freeimages)
-        }
-        else {
-            None
-        }
+        self.free_images
     }
 }
 
 /// Types of storage hardware.
-#[derive(Debug)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
 pub enum StorageType {
     /// A fixed ROM storage.
     FixedRom,
@@ -179,7 +122,8 @@ pub enum StorageType {
 }
 
 /// Types of filesystem hierarchies.
-#[derive(Debug)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
 pub enum FilesystemType {
     /// All files stored in one directory.
     Flat,
@@ -199,7 +143,8 @@ pub enum FilesystemType {
 }
 
 /// Types of access permissions.
-#[derive(Debug)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
 pub enum AccessType {
     /// Read and write operations are allowed.
     ReadWrite,
@@ -210,3 +155,99 @@ pub enum AccessType {
     /// Only read operations are allowed.
     ReadOnly,
 }
+
+
+#[doc(hidden)]
+pub fn from_libgphoto2(info: &::gphoto2::CameraStorageInformation) -> Storage {
+    Storage {
+        base_dir: if info.fields & ::gphoto2::GP_STORAGEINFO_BASE != 0 {
+            Some(util::owned_str(&info.basedir))
+        }
+        else {
+            None
+        },
+
+        label: if info.fields & ::gphoto2::GP_STORAGEINFO_LABEL != 0 {
+            Some(util::owned_str(&info.label))
+        }
+        else {
+            None
+        },
+
+        description: if info.fields & ::gphoto2::GP_STORAGEINFO_DESCRIPTION != 0 {
+            Some(util::owned_str(&info.description))
+        }
+        else {
+            None
+        },
+
+        storage_type: if info.fields & ::gphoto2::GP_STORAGEINFO_STORAGETYPE != 0 {
+            Some(match info.storage_type {
+                ::gphoto2::GP_STORAGEINFO_ST_FIXED_ROM     => StorageType::FixedRom,
+                ::gphoto2::GP_STORAGEINFO_ST_REMOVABLE_ROM => StorageType::RemovableRom,
+                ::gphoto2::GP_STORAGEINFO_ST_FIXED_RAM     => StorageType::FixedRam,
+                ::gphoto2::GP_STORAGEINFO_ST_REMOVABLE_RAM => StorageType::RemoveableRam,
+                ::gphoto2::GP_STORAGEINFO_ST_UNKNOWN       => StorageType::Unknown,
+            })
+        }
+        else {
+            None
+        },
+
+        filesystem_type: if info.fields & ::gphoto2::GP_STORAGEINFO_FILESYSTEMTYPE != 0 {
+            Some(match info.fstype {
+                ::gphoto2::GP_STORAGEINFO_FST_GENERICFLAT         => FilesystemType::Flat,
+                ::gphoto2::GP_STORAGEINFO_FST_GENERICHIERARCHICAL => FilesystemType::Hierarchical,
+                ::gphoto2::GP_STORAGEINFO_FST_DCF                 => FilesystemType::DCF,
+                ::gphoto2::GP_STORAGEINFO_FST_UNDEFINED           => FilesystemType::Unknown,
+            })
+        }
+        else {
+            None
+        },
+
+        access_type: if info.fields & ::gphoto2::GP_STORAGEINFO_ACCESS != 0 {
+            Some(match info.access {
+                ::gphoto2::GP_STORAGEINFO_AC_READWRITE            => AccessType::ReadWrite,
+                ::gphoto2::GP_STORAGEINFO_AC_READONLY             => AccessType::ReadOnly,
+                ::gphoto2::GP_STORAGEINFO_AC_READONLY_WITH_DELETE => AccessType::ReadDelete,
+            })
+        }
+        else {
+            None
+        },
+
+        capacity_kbytes: if info.fields & ::gphoto2::GP_STORAGEINFO_MAXCAPACITY != 0 {
+            Some(info.capacitykbytes)
+        }
+        else {
+            None
+        },
+
+        free_kbytes: if info.fields & ::gphoto2::GP_STORAGEINFO_FREESPACEKBYTES != 0 {
+            Some(info.freekbytes)
+        }
+        else {
+            None
+        },
+
+        free_images: if info.fields & ::gphoto2::GP_STORAGEINFO_FREESPACEIMAGES != 0 {
+            Some(info.freeimages)
+        }
+        else {
+            None
+        },
+    }
+}
+
+mod util {
+    use std::ffi::CStr;
+
+    use ::libc::c_char;
+
+    pub fn owned_str(chars: &[c_char]) -> String {
+        unsafe {
+            String::from_utf8_lossy(CStr::from_ptr(chars.as_ptr()).to_bytes()).into_owned()
+        }
+    }
+}
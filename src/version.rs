@@ -25,6 +25,7 @@ use std::str;
 /// libgphoto2 2.5.7 all camlibs clang ltdl no EXIF
 /// ```
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct LibraryVersion {
     version: &'static str,
     camlibs: &'static str,
@@ -0,0 +1,118 @@
+use std::collections::VecDeque;
+
+use ::camera::{Camera,CameraFile};
+use ::context::Context;
+
+/// An iterator over the files below a folder on a camera's storage.
+///
+/// A `Walk` is created by `Camera::walk()`. It visits folders depth-first and yields every file
+/// it finds. An error while listing a folder is yielded as an item, after which the walk continues
+/// with the remaining folders, unless the operation was canceled.
+///
+/// ## Example
+///
+/// ```no_run
+/// let mut context = gphoto::Context::new().unwrap();
+/// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+///
+/// for file in camera.walk(&mut context, "/store_00010001").max_depth(3).filter_folders(|f| !f.ends_with("/MISC")) {
+///     match file {
+///         Ok(file) => println!("{}/{}", file.directory(), file.basename()),
+///         Err(err) => println!("error: {}", err),
+///     }
+/// }
+/// ```
+pub struct Walk<'a> {
+    camera: &'a mut Camera,
+    context: &'a mut Context,
+    max_depth: Option<usize>,
+    filter: Option<Box<dyn FnMut(&str) -> bool + 'a>>,
+    folders: Vec<(String,usize)>,
+    files: VecDeque<CameraFile>,
+}
+
+impl<'a> Walk<'a> {
+    /// Limits how deep the walk descends into subfolders.
+    ///
+    /// A depth of `0` yields only the files directly inside the root folder, a depth of `1` also
+    /// yields the files in the root's immediate subfolders, and so on. By default, there is no
+    /// limit.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Only descends into subfolders for which `predicate` returns `true`.
+    ///
+    /// The predicate receives the absolute path of each subfolder. The root folder is always
+    /// visited.
+    pub fn filter_folders<F>(mut self, predicate: F) -> Self
+        where F: FnMut(&str) -> bool + 'a
+    {
+        self.filter = Some(Box::new(predicate));
+        self
+    }
+
+    fn visit(&mut self, folder: String, depth: usize) -> ::Result<()> {
+        let files = self.camera.list_files(self.context, &folder)?;
+        self.files.extend(files);
+
+        if self.max_depth.map_or(true, |max| depth < max) {
+            let subfolders = self.camera.list_folders(self.context, &folder)?;
+
+            // Pushed in reverse so that subfolders are visited in the order they were listed.
+            for subfolder in subfolders.into_iter().rev() {
+                let visit = match self.filter {
+                    Some(ref mut predicate) => predicate(&subfolder),
+                    None => true,
+                };
+
+                if visit {
+                    self.folders.push((subfolder, depth + 1));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = ::Result<CameraFile>;
+
+    fn next(&mut self) -> Option<::Result<CameraFile>> {
+        loop {
+            if let Some(file) = self.files.pop_front() {
+                return Some(Ok(file));
+            }
+
+            match self.folders.pop() {
+                Some((folder, depth)) => {
+                    if let Err(err) = self.visit(folder, depth) {
+                        // A canceled walk ends with the error instead of moving on.
+                        if err.kind() == ::error::ErrorKind::Cancel {
+                            self.folders.clear();
+                            self.files.clear();
+                        }
+
+                        return Some(Err(err));
+                    }
+                },
+                None => return None,
+            }
+        }
+    }
+}
+
+
+#[doc(hidden)]
+pub fn new<'a>(camera: &'a mut Camera, context: &'a mut Context, root: &str) -> Walk<'a> {
+    Walk {
+        camera: camera,
+        context: context,
+        max_depth: None,
+        filter: None,
+        folders: vec![(root.to_owned(), 0)],
+        files: VecDeque::new(),
+    }
+}
@@ -0,0 +1,428 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::ffi::{CStr,CString};
+use std::ptr;
+
+use ::libc::{c_char,c_float,c_int,c_void};
+
+use ::handle::{Handle,HandleMut};
+
+/// Types of configuration widgets.
+#[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub enum WidgetType {
+    /// Top-level configuration window.
+    Window,
+
+    /// Section of related widgets.
+    Section,
+
+    /// Free-form text value.
+    Text,
+
+    /// Numeric value within a range.
+    Range,
+
+    /// Boolean value.
+    Toggle,
+
+    /// Choice between several values, usually displayed as radio buttons.
+    Radio,
+
+    /// Choice between several values, usually displayed as a drop-down menu.
+    Menu,
+
+    /// Button that triggers an action on the camera.
+    Button,
+
+    /// Date and time value.
+    Date,
+}
+
+/// Value of a configuration widget.
+#[derive(Debug,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub enum WidgetValue {
+    /// Value of a `Text` widget.
+    Text(String),
+
+    /// Value of a `Range` widget.
+    Range(f32),
+
+    /// Value of a `Toggle` widget.
+    Toggle(bool),
+
+    /// Value of a `Radio` or `Menu` widget.
+    Choice(String),
+
+    /// Value of a `Date` widget as seconds since the Unix epoch.
+    Date(i64),
+}
+
+/// A node in a camera's configuration tree.
+///
+/// A camera's configuration is represented as a tree of widgets. The root of the tree is a
+/// `Window`, which usually contains `Section` widgets that group the widgets holding the actual
+/// settings.
+///
+/// Changing a widget's value only affects the local copy of the configuration. The changes are
+/// applied to the camera with `Camera::set_config()`.
+///
+/// ## Example
+///
+/// A `Widget` can be used to print a camera's configuration:
+///
+/// ```no_run
+/// fn print_widget(widget: &gphoto::Widget, depth: usize) {
+///     println!("{:indent$}{} ({:?}) = {:?}",
+///              "", widget.name(), widget.widget_type(), widget.value(), indent = depth * 2);
+///
+///     for child in widget.children() {
+///         print_widget(&child, depth + 1);
+///     }
+/// }
+///
+/// let mut context = gphoto::Context::new().unwrap();
+/// let mut camera = gphoto::Camera::autodetect(&mut context).unwrap();
+/// let config = camera.config(&mut context).unwrap();
+///
+/// print_widget(&config, 0);
+/// ```
+///
+/// The above example may print something like the following:
+///
+/// ```text
+/// main (Window) = Ok(None)
+///   settings (Section) = Ok(None)
+///     datetime (Date) = Ok(Some(Date(1445990400)))
+///   imgsettings (Section) = Ok(None)
+///     iso (Radio) = Ok(Some(Choice("400")))
+/// ```
+pub struct Widget {
+    // The root widget owns the memory for the whole tree, so every widget holds a reference to
+    // the root.
+    root: *mut ::gphoto2::CameraWidget,
+    widget: *mut ::gphoto2::CameraWidget,
+}
+
+impl Drop for Widget {
+    fn drop(&mut self) {
+        unsafe {
+            ::gphoto2::gp_widget_unref(self.root);
+        }
+    }
+}
+
+impl Widget {
+    /// Returns the name of the widget.
+    ///
+    /// The name is a short identifier for the setting, e.g., `"iso"` or `"shutterspeed"`.
+    pub fn name(&self) -> Cow<str> {
+        let mut name = ptr::null();
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_widget_get_name(self.widget, &mut name));
+            String::from_utf8_lossy(CStr::from_ptr(name).to_bytes())
+        }
+    }
+
+    /// Returns the human-readable label of the widget.
+    pub fn label(&self) -> Cow<str> {
+        let mut label = ptr::null();
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_widget_get_label(self.widget, &mut label));
+            String::from_utf8_lossy(CStr::from_ptr(label).to_bytes())
+        }
+    }
+
+    /// Returns additional information about the widget.
+    pub fn info(&self) -> Cow<str> {
+        let mut info = ptr::null();
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_widget_get_info(self.widget, &mut info));
+            String::from_utf8_lossy(CStr::from_ptr(info).to_bytes())
+        }
+    }
+
+    /// Returns the type of the widget.
+    pub fn widget_type(&self) -> WidgetType {
+        let mut widget_type = ::gphoto2::CameraWidgetType::GP_WIDGET_WINDOW;
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_widget_get_type(self.widget, &mut widget_type));
+        }
+
+        match widget_type {
+            ::gphoto2::CameraWidgetType::GP_WIDGET_WINDOW  => WidgetType::Window,
+            ::gphoto2::CameraWidgetType::GP_WIDGET_SECTION => WidgetType::Section,
+            ::gphoto2::CameraWidgetType::GP_WIDGET_TEXT    => WidgetType::Text,
+            ::gphoto2::CameraWidgetType::GP_WIDGET_RANGE   => WidgetType::Range,
+            ::gphoto2::CameraWidgetType::GP_WIDGET_TOGGLE  => WidgetType::Toggle,
+            ::gphoto2::CameraWidgetType::GP_WIDGET_RADIO   => WidgetType::Radio,
+            ::gphoto2::CameraWidgetType::GP_WIDGET_MENU    => WidgetType::Menu,
+            ::gphoto2::CameraWidgetType::GP_WIDGET_BUTTON  => WidgetType::Button,
+            ::gphoto2::CameraWidgetType::GP_WIDGET_DATE    => WidgetType::Date,
+        }
+    }
+
+    /// Returns `true` if the widget's value can not be changed.
+    pub fn is_readonly(&self) -> bool {
+        let mut readonly: c_int = 0;
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_widget_get_readonly(self.widget, &mut readonly));
+        }
+
+        readonly != 0
+    }
+
+    /// Returns the widget's children.
+    ///
+    /// Only `Window` and `Section` widgets have children.
+    pub fn children(&self) -> Vec<Widget> {
+        let count = unsafe { ::gphoto2::gp_widget_count_children(self.widget) };
+
+        (0..count).map(|i| {
+            let mut child = ptr::null_mut();
+
+            unsafe {
+                assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_widget_get_child(self.widget, i, &mut child));
+            }
+
+            self.borrow_node(child)
+        }).collect()
+    }
+
+    /// Finds a widget in the subtree below this widget by its name.
+    ///
+    /// Returns `None` if no widget with the given name exists.
+    pub fn child_by_name(&self, name: &str) -> Option<Widget> {
+        let cstr = match CString::new(name) {
+            Ok(s) => s,
+            Err(_) => return None
+        };
+
+        let mut child = ptr::null_mut();
+
+        match unsafe { ::gphoto2::gp_widget_get_child_by_name(self.widget, cstr.as_ptr(), &mut child) } {
+            ::gphoto2::GP_OK => Some(self.borrow_node(child)),
+            _ => None
+        }
+    }
+
+    /// Returns the possible values of a `Radio` or `Menu` widget.
+    ///
+    /// Returns an empty `Vec` for all other types of widgets.
+    pub fn choices(&self) -> Vec<String> {
+        match self.widget_type() {
+            WidgetType::Radio | WidgetType::Menu => (),
+            _ => return Vec::new(),
+        }
+
+        let count = unsafe { ::gphoto2::gp_widget_count_choices(self.widget) };
+
+        (0..count).map(|i| {
+            let mut choice = ptr::null();
+
+            unsafe {
+                assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_widget_get_choice(self.widget, i, &mut choice));
+                String::from_utf8_lossy(CStr::from_ptr(choice).to_bytes()).into_owned()
+            }
+        }).collect()
+    }
+
+    /// Returns the minimum, maximum, and increment of a `Range` widget.
+    ///
+    /// Returns `None` for all other types of widgets.
+    pub fn range(&self) -> Option<(f32,f32,f32)> {
+        if self.widget_type() != WidgetType::Range {
+            return None;
+        }
+
+        let mut min: c_float = 0.0;
+        let mut max: c_float = 0.0;
+        let mut increment: c_float = 0.0;
+
+        unsafe {
+            assert_eq!(::gphoto2::GP_OK, ::gphoto2::gp_widget_get_range(self.widget, &mut min, &mut max, &mut increment));
+        }
+
+        Some((min as f32, max as f32, increment as f32))
+    }
+
+    /// Returns the widget's value.
+    ///
+    /// Returns `None` for widgets that don't hold a value (`Window`, `Section`, and `Button`).
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the value could not be retrieved:
+    ///
+    /// * `CorruptedData` if a text value is invalid UTF-8.
+    pub fn value(&self) -> ::Result<Option<WidgetValue>> {
+        match self.widget_type() {
+            WidgetType::Window | WidgetType::Section | WidgetType::Button => Ok(None),
+            WidgetType::Text => self.string_value().map(|s| Some(WidgetValue::Text(s))),
+            WidgetType::Radio | WidgetType::Menu => self.string_value().map(|s| Some(WidgetValue::Choice(s))),
+            WidgetType::Range => {
+                let mut value: c_float = 0.0;
+
+                try_unsafe!(::gphoto2::gp_widget_get_value(self.widget, &mut value as *mut c_float as *mut c_void));
+
+                Ok(Some(WidgetValue::Range(value as f32)))
+            },
+            WidgetType::Toggle => {
+                let mut value: c_int = 0;
+
+                try_unsafe!(::gphoto2::gp_widget_get_value(self.widget, &mut value as *mut c_int as *mut c_void));
+
+                Ok(Some(WidgetValue::Toggle(value != 0)))
+            },
+            WidgetType::Date => {
+                let mut value: c_int = 0;
+
+                try_unsafe!(::gphoto2::gp_widget_get_value(self.widget, &mut value as *mut c_int as *mut c_void));
+
+                Ok(Some(WidgetValue::Date(value as i64)))
+            },
+        }
+    }
+
+    /// Sets the widget's value.
+    ///
+    /// The new value is only stored in the local copy of the configuration. Use
+    /// `Camera::set_config()` to apply the change to the camera.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the value could not be set:
+    ///
+    /// * `InvalidInput` if the type of value doesn't match the type of the widget, if a text
+    ///   value contains a nul byte, or if a date is outside the range supported by `libgphoto2`.
+    pub fn set_value(&mut self, value: &WidgetValue) -> ::Result<()> {
+        match (self.widget_type(), value) {
+            (WidgetType::Text, &WidgetValue::Text(ref s)) |
+            (WidgetType::Radio, &WidgetValue::Choice(ref s)) |
+            (WidgetType::Menu, &WidgetValue::Choice(ref s)) => {
+                let cstr = match CString::new(s.as_bytes()) {
+                    Ok(s) => s,
+                    Err(_) => return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_BAD_PARAMETERS))
+                };
+
+                try_unsafe!(::gphoto2::gp_widget_set_value(self.widget, cstr.as_ptr() as *const c_void));
+            },
+            (WidgetType::Range, &WidgetValue::Range(n)) => {
+                let value = n as c_float;
+
+                try_unsafe!(::gphoto2::gp_widget_set_value(self.widget, &value as *const c_float as *const c_void));
+            },
+            (WidgetType::Toggle, &WidgetValue::Toggle(b)) => {
+                let value = b as c_int;
+
+                try_unsafe!(::gphoto2::gp_widget_set_value(self.widget, &value as *const c_int as *const c_void));
+            },
+            (WidgetType::Date, &WidgetValue::Date(t)) => {
+                // Dates are stored as a C `int`, so times that don't fit are rejected rather than
+                // wrapped around to a different time.
+                let value = match c_int::try_from(t) {
+                    Ok(value) => value,
+                    Err(_) => return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_BAD_PARAMETERS))
+                };
+
+                try_unsafe!(::gphoto2::gp_widget_set_value(self.widget, &value as *const c_int as *const c_void));
+            },
+            _ => return Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_BAD_PARAMETERS))
+        }
+
+        Ok(())
+    }
+
+    /// Sets the widget's value from its textual representation.
+    ///
+    /// The string is interpreted according to the type of the widget:
+    ///
+    /// * `Text`, `Radio`, and `Menu` widgets use the string as-is.
+    /// * `Range` widgets parse the string as a floating point number.
+    /// * `Toggle` widgets accept `"1"`, `"on"`, or `"true"` and `"0"`, `"off"`, or `"false"`.
+    /// * `Date` widgets parse the string as seconds since the Unix epoch.
+    ///
+    /// ## Errors
+    ///
+    /// This function returns an error if the value could not be set:
+    ///
+    /// * `InvalidInput` if the string can not be interpreted as a value for the widget, if the
+    ///   widget doesn't hold a value, or if a date is outside the range supported by `libgphoto2`.
+    pub fn set_value_from_str(&mut self, value: &str) -> ::Result<()> {
+        let parsed = match self.widget_type() {
+            WidgetType::Text => Some(WidgetValue::Text(value.to_owned())),
+            WidgetType::Radio | WidgetType::Menu => Some(WidgetValue::Choice(value.to_owned())),
+            WidgetType::Range => value.trim().parse().ok().map(WidgetValue::Range),
+            WidgetType::Date => value.trim().parse().ok().map(WidgetValue::Date),
+            WidgetType::Toggle => {
+                match &*value.trim().to_lowercase() {
+                    "1" | "on" | "true" => Some(WidgetValue::Toggle(true)),
+                    "0" | "off" | "false" => Some(WidgetValue::Toggle(false)),
+                    _ => None
+                }
+            },
+            WidgetType::Window | WidgetType::Section | WidgetType::Button => None,
+        };
+
+        match parsed {
+            Some(v) => self.set_value(&v),
+            None => Err(::error::from_libgphoto2(::gphoto2::GP_ERROR_BAD_PARAMETERS))
+        }
+    }
+
+    fn string_value(&self) -> ::Result<String> {
+        let mut value: *const c_char = ptr::null();
+
+        try_unsafe!(::gphoto2::gp_widget_get_value(self.widget, &mut value as *mut *const c_char as *mut c_void));
+
+        if value.is_null() {
+            return Ok(String::new());
+        }
+
+        let bytes = unsafe { CStr::from_ptr(value).to_bytes() };
+
+        String::from_utf8(bytes.to_vec()).map_err(|_| {
+            ::error::from_libgphoto2(::gphoto2::GP_ERROR_CORRUPTED_DATA)
+        })
+    }
+
+    fn borrow_node(&self, widget: *mut ::gphoto2::CameraWidget) -> Widget {
+        unsafe {
+            ::gphoto2::gp_widget_ref(self.root);
+        }
+
+        Widget {
+            root: self.root,
+            widget: widget,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl Handle<::gphoto2::CameraWidget> for Widget {
+    unsafe fn as_ptr(&self) -> *const ::gphoto2::CameraWidget {
+        self.widget
+    }
+}
+
+#[doc(hidden)]
+impl HandleMut<::gphoto2::CameraWidget> for Widget {
+    unsafe fn as_mut_ptr(&mut self) -> *mut ::gphoto2::CameraWidget {
+        self.widget
+    }
+}
+
+/// Takes ownership of a widget tree returned by `libgphoto2`.
+#[doc(hidden)]
+pub fn from_libgphoto2(root: *mut ::gphoto2::CameraWidget) -> Widget {
+    Widget {
+        root: root,
+        widget: root,
+    }
+}